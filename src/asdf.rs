@@ -1,27 +1,41 @@
 use std::{
-    ffi::OsStr,
-    process::{Command, Output},
+    ffi::{OsStr, OsString},
+    fmt::Debug,
+    path::PathBuf,
+    sync::{Arc, OnceLock},
+    time::Duration,
 };
 // use strum;
 use getset::Getters;
 use thiserror::Error;
 
+use crate::{
+    classify::{self, Subcommand, Subject},
+    runner::{
+        shell_escape, CancelHandle, Interruption, Invocation, OutputEvent, RunOutput, Runner,
+        SystemRunner,
+    },
+    AsdfVersion,
+};
+
 /// This enumerable represents all possible [`Err`] values of the [`Result`] alias used by this library.
 ///
+/// Variants for a command that ran but did not succeed carry a [`Failure`] with its exit code and output,
+/// which is also available from [`Error::failure`].
 #[derive(Debug, Error)]
 pub enum Error {
-    /// When a call to [`std::process::Command::output`] fails to run a command,
+    /// When a call to [`Runner::run`] fails to run a command,
     /// the [`std::io::Error`] will be wrapped unless a specific case is handled by another variant.
     #[error("unable to create command")]
     IoError(std::io::Error),
-    /// When standard output and error from a call to [`std::process::Command::output`] do not
+    /// When standard output and error from a call to [`Runner::run`] do not
     /// convert to valid unicode.
     #[error("failed to get command output as UTF-8")]
     BadEncoding(#[from] std::string::FromUtf8Error),
     /// This variant will be used after checking the command output against all cases that warrant other
-    /// variants, and if the [`std::process::Output::status`] is non-zero.
-    #[error("command terminated unsuccessfully")]
-    FailedCommand(Command),
+    /// variants, and if the [`RunOutput::code`] is non-zero.
+    #[error("command terminated unsuccessfully: {0}")]
+    FailedCommand(Box<Failure>),
     /// Instead of returning [`Error::IoError`] when the operating system
     /// reports code `2` ([`std::io::ErrorKind::NotFound`]), this variant is used.
     #[error("asdf command was not found on this system")]
@@ -29,23 +43,179 @@ pub enum Error {
     /// An parameter to a function (argument to a command) was incorrect. Generally this is caused by spaces
     /// in a string passed as a parameter, though the shell or Rust should escape these for you.
     /// It is more likely that there were illegal characters passed to `asdf`.
-    #[error("the parameters passed were incorrect or malformed")]
-    MalformedOptions(Command),
+    #[error("the parameters passed were incorrect or malformed: {0}")]
+    MalformedOptions(Box<Failure>),
     /// This is an error within the library. Perhaps an update to `asdf` broke this, and the output or
     /// formatting of a certain command has changed. Please notify the mainainer of this library if this
     /// variant is passed to a caller.
-    #[error("the output from the command was unexpected")]
-    MalformedOutput(Command),
+    #[error("the output from the command was unexpected: {0}")]
+    MalformedOutput(Box<Failure>),
     /// An `asdf` command needed to access the internet to do something and the system had no connection.
-    #[error("there is no internet connection")]
-    NoInternet,
+    #[error("there is no internet connection: {0}")]
+    NoInternet(Box<Failure>),
     /// The plugin name that was provided as an argument to an `asdf` command
     /// was not installed or does not exist in the repository.
-    #[error("the plugin requested was not found in the repository")]
-    PluginNotFound,
+    #[error("the plugin `{name}` was not found in the repository: {failure}")]
+    PluginNotFound { name: String, failure: Box<Failure> },
     /// The plugin that you requested to install is already available on the system.
-    #[error("the plugin requested was already added")]
-    PluginAlreadyAdded,
+    #[error("the plugin `{name}` was already added: {failure}")]
+    PluginAlreadyAdded { name: String, failure: Box<Failure> },
+    /// The plugin does not provide a version matching the one that was requested,
+    /// such as a `latest:<version>` prefix that no version begins with.
+    #[error("no version of `{name}` matches `{version}`: {failure}")]
+    VersionNotFound {
+        name: String,
+        version: String,
+        failure: Box<Failure>,
+    },
+    /// The version that was given is not installed, so there is nothing to remove or locate.
+    /// When no version was given, `version` is [`None`] and the version that is set is not installed.
+    #[error("{} of `{name}` is not installed: {failure}", describe_version(.version))]
+    VersionNotInstalled {
+        name: String,
+        version: Option<String>,
+        failure: Box<Failure>,
+    },
+    /// No version is set for any tool that provides the command. `versions` holds every plugin and version
+    /// that provides it, one of which can be set with [`set`](crate::packages::set) to use the command.
+    #[error("no version is set for the command `{command}`: {failure}")]
+    CommandVersionNotSet {
+        command: String,
+        versions: Vec<(String, String)>,
+        failure: Box<Failure>,
+    },
+    /// No installed version of any tool provides the command, or it has no shim yet,
+    /// which is created by [`reshim`](crate::utils::reshim).
+    #[error("the command `{command}` was not found: {failure}")]
+    CommandNotFound {
+        command: String,
+        failure: Box<Failure>,
+    },
+    /// The plugin does not have the script that the command needs, such as `bin/list-all`.
+    #[error("the plugin `{name}` does not have a `{script}` script: {failure}")]
+    PluginScriptMissing {
+        name: String,
+        script: &'static str,
+        failure: Box<Failure>,
+    },
+    /// The version of the tool that is set is `system`, so it is not managed by `asdf`
+    /// and has no install directory.
+    #[error("the system version of `{name}` is selected: {failure}")]
+    SystemVersion { name: String, failure: Box<Failure> },
+    /// No version of the tool is set by any `.tool-versions` file or environment variable
+    /// that applies to the working directory.
+    #[error("no version of `{name}` is set: {failure}")]
+    NoVersionSet { name: String, failure: Box<Failure> },
+    /// `asdf` can not update itself because it was not installed with a Git clone,
+    /// such as with Homebrew or a distribution package. [`update`](crate::utils::update)
+    /// returns this as [`UpdateOutcome::Unsupported`](crate::utils::UpdateOutcome::Unsupported) instead.
+    #[error("asdf can not update itself: {0}")]
+    UpdateDisabled(Box<Failure>),
+    /// The command did not exit before the timeout set by [`Asdf::with_timeout`] and was killed.
+    /// Whatever the command wrote before then is kept, decoded lossily.
+    #[error("command timed out: {0}")]
+    TimedOut(Box<Failure>),
+    /// The command was killed because the handle set by [`Asdf::with_cancel_handle`] was cancelled.
+    /// Whatever the command wrote before then is kept, decoded lossily.
+    #[error("command was cancelled: {0}")]
+    Cancelled(Box<Failure>),
+    /// The command does not exist in the version of `asdf` that is installed, see [`Asdf::version`].
+    #[error("`asdf {command}` is not available in asdf {version}")]
+    Unsupported {
+        command: &'static str,
+        version: AsdfVersion,
+    },
+}
+
+impl Error {
+    /// The command that failed and its output, if the error was caused by a command that ran.
+    pub fn failure(&self) -> Option<&Failure> {
+        match self {
+            Error::FailedCommand(failure)
+            | Error::MalformedOptions(failure)
+            | Error::MalformedOutput(failure)
+            | Error::NoInternet(failure)
+            | Error::PluginNotFound { failure, .. }
+            | Error::PluginAlreadyAdded { failure, .. }
+            | Error::VersionNotFound { failure, .. }
+            | Error::VersionNotInstalled { failure, .. }
+            | Error::SystemVersion { failure, .. }
+            | Error::PluginScriptMissing { failure, .. }
+            | Error::CommandVersionNotSet { failure, .. }
+            | Error::CommandNotFound { failure, .. }
+            | Error::NoVersionSet { failure, .. }
+            | Error::UpdateDisabled(failure)
+            | Error::TimedOut(failure)
+            | Error::Cancelled(failure) => Some(failure),
+            Error::IoError(_)
+            | Error::BadEncoding(_)
+            | Error::AsdfNotFound
+            | Error::Unsupported { .. } => None,
+        }
+    }
+}
+
+fn describe_version(version: &Option<String>) -> String {
+    match version {
+        Some(version) => format!("version `{version}`"),
+        None => "the version that is set".to_owned(),
+    }
+}
+
+/// A command that ran but did not succeed, as carried by most [`enum@Error`] variants.
+///
+/// The [`Display`](std::fmt::Display) implementation shows the command line,
+/// the exit code, and the most relevant line of [`Failure::stderr`].
+#[derive(Clone, Debug, PartialEq, Eq, Getters)]
+#[getset(get = "pub")]
+pub struct Failure {
+    /// The command that was run, including the environment variables set by the client,
+    /// escaped so that it can be pasted into a POSIX shell.
+    command_line: String,
+    /// The exit code, or [`None`] if the command was terminated by a signal or killed.
+    code: Option<i32>,
+    /// Everything that the command wrote to standard output.
+    stdout: String,
+    /// Everything that the command wrote to standard error.
+    stderr: String,
+}
+
+impl Failure {
+    fn new(invocation: &Invocation, code: Option<i32>, stdout: String, stderr: String) -> Self {
+        Self {
+            command_line: invocation.command_line(),
+            code,
+            stdout,
+            stderr,
+        }
+    }
+
+    /// The line of [`Failure::stderr`] that best describes the error. This is the last line that mentions
+    /// an error, since a failed build prints a lot before giving up, otherwise the first line that is not empty.
+    pub fn summary(&self) -> Option<&str> {
+        let mut lines = self.stderr.lines().map(str::trim).filter(|x| !x.is_empty());
+
+        lines
+            .clone()
+            .rev()
+            .find(|x| x.to_lowercase().contains("error"))
+            .or_else(|| lines.next())
+    }
+}
+
+impl std::fmt::Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "`{}`", self.command_line)?;
+
+        match self.code {
+            Some(code) => write!(f, " exited with code {code}")?,
+            None => write!(f, " was terminated")?,
+        }
+        match self.summary() {
+            Some(summary) => write!(f, ": {summary}"),
+            None => Ok(()),
+        }
+    }
 }
 
 impl From<std::io::Error> for Error {
@@ -60,422 +230,2999 @@ impl From<std::io::Error> for Error {
 /// An alias of [`std::result::Result`] specific to this library.
 pub type Result<T> = std::result::Result<T, Error>;
 
-#[derive(Debug)]
-struct CommandResult {
-    command: Command,
-    output: Output,
-    stdout: String,
-    stderr: String,
+/// A client for the `asdf` executable, holding the path to the binary and the environment
+/// that every command is run with. All commands are available as methods on this type,
+/// and the free functions in [`plugins`], [`packages`] and [`utils`] use a default client.
+///
+/// Fields that are [`None`] are not set, and the value inherited from the current process
+/// (if any) is what `asdf` will see.
+#[derive(Clone, Debug, Getters)]
+#[getset(get = "pub")]
+pub struct Asdf {
+    /// Path to the `asdf` executable, by default `asdf` is resolved from `PATH`.
+    binary: PathBuf,
+    /// Passed to `asdf` as `ASDF_DIR`, the location of the `asdf` installation.
+    dir: Option<PathBuf>,
+    /// Passed to `asdf` as `ASDF_DATA_DIR`, where plugins, installs and shims are stored.
+    data_dir: Option<PathBuf>,
+    /// Passed to `asdf` as `ASDF_CONFIG_FILE`, the location of the `.asdfrc` file.
+    config_file: Option<PathBuf>,
+    /// Passed to `asdf` as `ASDF_DEFAULT_TOOL_VERSIONS_FILENAME`, the name of the file
+    /// that versions are read from and written to, instead of `.tool-versions`.
+    default_tool_versions_filename: Option<OsString>,
+    /// Additional environment variables set for every command, applied last.
+    envs: Vec<(OsString, OsString)>,
+    /// The working directory of every command, which decides the `.tool-versions` files that apply.
+    /// By default this is the working directory of the current process.
+    current_dir: Option<PathBuf>,
+    /// Starts the processes for each command, by default [`SystemRunner`].
+    runner: Arc<dyn Runner>,
+    /// Receives each line of output while a command is running, see [`Asdf::with_output_handler`].
+    #[getset(skip)]
+    output_handler: Option<OutputHandler>,
+    /// How long each command may run before it is killed, see [`Asdf::with_timeout`].
+    timeout: Option<Duration>,
+    /// Kills the running command when cancelled, see [`Asdf::with_cancel_handle`].
+    cancel_handle: Option<CancelHandle>,
+    /// The version of `asdf` set by [`Asdf::with_version`].
+    #[getset(skip)]
+    version: Option<AsdfVersion>,
+    /// The version of `asdf` detected by [`Asdf::version`], shared between clones.
+    #[getset(skip)]
+    detected_version: Arc<OnceLock<AsdfVersion>>,
 }
 
-fn command<S, I>(run: I) -> Result<CommandResult>
-where
-    S: AsRef<OsStr>,
-    I: IntoIterator<Item = S>,
-{
-    let mut run = run.into_iter();
-    let mut command = Command::new(run.next().unwrap());
-
-    command.args(run);
-
-    let output = command.output()?;
-    let stdout = String::from_utf8(output.stdout.clone())?;
-    let stderr = String::from_utf8(output.stderr.clone())?;
-
-    Ok(CommandResult {
-        command,
-        output,
-        stdout,
-        stderr,
-    })
+/// A callback set by [`Asdf::with_output_handler`].
+#[derive(Clone)]
+struct OutputHandler(Arc<dyn Fn(OutputEvent<'_>) + Send + Sync>);
+
+impl Debug for OutputHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("OutputHandler")
+    }
 }
 
-/// MANAGE PLUGINS
-pub mod plugins {
-    use super::*;
+impl Default for Asdf {
+    fn default() -> Self {
+        Self {
+            binary: PathBuf::from("asdf"),
+            dir: None,
+            data_dir: None,
+            config_file: None,
+            default_tool_versions_filename: None,
+            envs: Vec::new(),
+            current_dir: None,
+            runner: Arc::new(SystemRunner),
+            output_handler: None,
+            timeout: None,
+            cancel_handle: None,
+            version: None,
+            detected_version: Arc::default(),
+        }
+    }
+}
 
-    /// Structure representing an `asdf` plugin as returned from [`list`] or [`list_all`].
-    #[derive(Clone, Debug, PartialEq, Getters)]
-    #[getset(get)]
-    pub struct Plugin {
-        /// Name of the plugin as specified in the plugin repository, or by the argument when
-        /// installed from a Git clone URL.
-        name: String,
-        /// The Git clone URL for this plugin.
-        git_url: String,
-        /// The Git branch that is used for the installed version of this plugin.
-        git_branch: Option<String>,
-        /// The Git reference that is used for the installed version of this plugin.
-        git_ref: Option<String>,
+impl Asdf {
+    /// Create a client that runs `asdf` from `PATH` with the environment of the current process.
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    pub type PluginSet = Vec<Plugin>;
+    /// Use the `asdf` executable at this path.
+    pub fn with_binary<P: Into<PathBuf>>(mut self, binary: P) -> Self {
+        self.binary = binary.into();
+        self.detected_version = Arc::default();
+        self
+    }
 
-    impl TryFrom<&str> for Plugin {
-        type Error = &'static str;
+    /// Set `ASDF_DIR` for every command.
+    pub fn with_dir<P: Into<PathBuf>>(mut self, dir: P) -> Self {
+        self.dir = Some(dir.into());
+        self.detected_version = Arc::default();
+        self
+    }
 
-        /// Given a [`&str`], this function splits at whitespace and places the results into the fields of this struct
-        /// in the order they are defined. The first two, `name` and `git_url` are mandatory, whereas
-        /// `git_branch` and `git_ref` are assumed to be [`None`] if the [`std::str::SplitWhitespace`]
-        /// iterator ends after `git_url`.
-        fn try_from(other: &str) -> std::result::Result<Self, Self::Error> {
-            let mut parts = other.split_whitespace();
+    /// Set `ASDF_DATA_DIR` for every command.
+    pub fn with_data_dir<P: Into<PathBuf>>(mut self, data_dir: P) -> Self {
+        self.data_dir = Some(data_dir.into());
+        self
+    }
 
-            Ok(Self {
-                name: match parts.next() {
-                    Some(name) => name.to_owned(),
-                    None => return Err("not enough iterations to retrieve field `name`"),
-                },
-                git_url: match parts.next() {
-                    Some(name) => name.to_owned(),
-                    None => return Err("not enough iterations to retrieve field `git_url`"),
-                },
-                git_branch: parts.next().map(|x| x.to_owned()),
-                git_ref: parts.next().map(|x| x.to_owned()),
-            })
-        }
+    /// Set `ASDF_CONFIG_FILE` for every command.
+    pub fn with_config_file<P: Into<PathBuf>>(mut self, config_file: P) -> Self {
+        self.config_file = Some(config_file.into());
+        self
     }
 
-    /// Add a plugin by name from the repository, or with a name by a valid Git clone URL.
-    /// See the [`asdf` plugin repository](https://github.com/asdf-vm/asdf-plugins#plugin-list)
-    /// for a complete list, or use `asdf plugin list all` or [`list_all`].
+    /// Set `ASDF_DEFAULT_TOOL_VERSIONS_FILENAME` for every command.
+    pub fn with_default_tool_versions_filename<S: Into<OsString>>(mut self, filename: S) -> Self {
+        self.default_tool_versions_filename = Some(filename.into());
+        self
+    }
+
+    /// Set an additional environment variable for every command.
     ///
-    /// ```help
-    /// asdf plugin add <name> [<git-url>]      Add a plugin from the plugin repo OR,
-    ///                                         add a Git repo as a plugin by
-    ///                                         specifying the name and repo url
-    /// ```
-    pub fn add<A, B>(name: A, git_url: Option<B>) -> Result<()>
+    /// As variables such as `ASDF_DIR` and `PATH` can change which `asdf` runs, the detected version is forgotten.
+    pub fn with_env<K, V>(mut self, key: K, value: V) -> Self
     where
-        A: AsRef<str>,
-        B: AsRef<str>,
+        K: Into<OsString>,
+        V: Into<OsString>,
     {
-        let mut run = vec!["asdf", "plugin", "list", name.as_ref()];
-
-        if let Some(git_url) = &git_url {
-            run.extend([git_url.as_ref()]);
-        }
-
-        let CommandResult {
-            command,
-            output,
-            stdout: _,
-            stderr,
-        } = command(run)?;
-
-        if stderr.contains("not found in repository") {
-            Err(Error::PluginNotFound)
-        } else if stderr.contains("already added") {
-            Err(Error::PluginAlreadyAdded)
-        } else if stderr.contains("usage: asdf") {
-            Err(Error::MalformedOptions(command))
-        } else if output.status.success() {
-            Ok(())
-        } else {
-            Err(Error::FailedCommand(command))
-        }
+        self.envs.push((key.into(), value.into()));
+        self.detected_version = Arc::default();
+        self
     }
 
-    /// Returns a [`PluginSet`] of [`Plugin`] for all plugins installed.
-    /// To get an iterable of available plugins, see [`list_all`].
-    /// Unlike the command, this function will always return a [`PluginSet`] with output from `--urls` and `--refs`.
+    /// Run every command in this directory, as if `asdf` was run from a shell in the project at this path.
     ///
-    /// ```help
-    /// asdf plugin list [--urls] [--refs]      List installed plugins. Optionally show
-    ///                                         git urls and git-ref
+    /// Only the child process is affected, so clients for different projects may be used from
+    /// several threads at once, which [`std::env::set_current_dir`] does not allow. A relative
+    /// [`Asdf::binary`] should be avoided, because it may be resolved from this directory.
+    ///
+    /// ```no_run
+    /// use asdf::Asdf;
+    ///
+    /// let project = Asdf::new().with_current_dir("/srv/project");
+    ///
+    /// project.local("nodejs", "20.1.0")?;
+    /// # Ok::<(), asdf::Error>(())
     /// ```
-    pub fn list() -> Result<PluginSet> {
-        let CommandResult {
-            command,
-            output,
-            stdout,
-            stderr,
-        } = command(["asdf", "plugin", "list", "--urls", "--refs"])?;
-
-        if stderr.contains("No plugins installed") {
-            Ok(PluginSet::new())
-        } else if output.status.success() {
-            stdout
-                .lines()
-                .map(|line| line.try_into())
-                .collect::<std::result::Result<PluginSet, _>>()
-                .or(Err(Error::MalformedOutput(command)))
-        } else {
-            Err(Error::FailedCommand(command))
-        }
+    pub fn with_current_dir<P: Into<PathBuf>>(mut self, current_dir: P) -> Self {
+        self.current_dir = Some(current_dir.into());
+        self
     }
 
-    /// ```help
-    /// asdf plugin list all                    List plugins registered on asdf-plugins
-    ///                                         repository with URLs
-    /// ```
-    pub fn list_all() -> Result<PluginSet> {
-        let CommandResult {
-            command,
-            output,
-            stdout,
-            stderr,
-        } = command(["asdf", "plugin", "list", "all"])?;
-
-        if stderr.contains("Could not resolve host") {
-            Err(Error::NoInternet)
-        } else if output.status.success() {
-            stdout
-                .replace("initializing plugin repository...", "")
-                .lines()
-                .map(|line| line.try_into())
-                .collect::<std::result::Result<PluginSet, _>>()
-                .or(Err(Error::MalformedOutput(command)))
-        } else {
-            Err(Error::FailedCommand(command))
-        }
+    /// Use `runner` to start processes instead of [`SystemRunner`].
+    pub fn with_runner<R: Runner + 'static>(mut self, runner: R) -> Self {
+        self.runner = Arc::new(runner);
+        self.detected_version = Arc::default();
+        self
     }
 
-    /// ```help
-    /// asdf plugin remove <name>               Remove plugin and package versions
+    /// Report each line of output to `handler` while a command is running, rather than only after it exits.
+    /// This is useful for long-running commands such as [`Asdf::plugin_add`], the return value and
+    /// any [`enum@Error`] are the same as without a handler.
+    ///
+    /// To stream the output of a single command, set the handler on a clone of the client.
+    ///
+    /// ```no_run
+    /// use asdf::{runner::OutputEvent, Asdf};
+    ///
+    /// let asdf = Asdf::new().with_output_handler(|event| match event {
+    ///     OutputEvent::Stdout(line) => println!("{line}"),
+    ///     OutputEvent::Stderr(line) => eprintln!("{line}"),
+    /// });
+    ///
+    /// asdf.plugin_add("nodejs", None::<&str>).unwrap();
     /// ```
-    pub fn remove<A>(name: A) -> !
+    pub fn with_output_handler<F>(mut self, handler: F) -> Self
     where
-        A: AsRef<str>,
+        F: Fn(OutputEvent<'_>) + Send + Sync + 'static,
     {
-        todo!();
+        self.output_handler = Some(OutputHandler(Arc::new(handler)));
+        self
     }
 
-    /// ```help
-    /// asdf plugin update <name> [<git-ref>]   Update a plugin to latest commit on
-    ///                                         default branch or a particular git-ref
-    /// ```
-    pub fn update<A, B>(name: A, git_ref: B) -> !
-    where
-        A: AsRef<str>,
-        B: AsRef<str>,
-    {
-        todo!();
+    /// Kill any command that runs for longer than `timeout`, along with every process it started,
+    /// and return [`Error::TimedOut`].
+    ///
+    /// To limit a single command, set the timeout on a clone of the client.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
     }
 
-    /// ```help
-    /// asdf plugin update --all                Update all plugins to latest commit on
-    ///                                         default branch
+    /// Kill the running command when `cancel_handle` is cancelled, along with every process it started,
+    /// and return [`Error::Cancelled`]. Commands that are started after the handle is cancelled are killed immediately.
+    ///
+    /// ```no_run
+    /// use asdf::{runner::CancelHandle, Asdf};
+    ///
+    /// let cancel = CancelHandle::new();
+    /// let asdf = Asdf::new().with_cancel_handle(cancel.clone());
+    ///
+    /// std::thread::spawn(move || asdf.plugin_add("nodejs", None::<&str>));
+    ///
+    /// cancel.cancel();
     /// ```
-    pub fn update_all() -> ! {
-        todo!();
+    pub fn with_cancel_handle(mut self, cancel_handle: CancelHandle) -> Self {
+        self.cancel_handle = Some(cancel_handle);
+        self
     }
-}
 
-/// MANAGE PACKAGES
-pub mod packages {
-    use super::*;
+    /// Assume that `asdf` is this version rather than detecting it, see [`Asdf::version`].
+    pub fn with_version(mut self, version: AsdfVersion) -> Self {
+        self.version = Some(version);
+        self
+    }
 
-    /// ```help
-    /// asdf install                            Install all the package versions listed
-    ///                                         in the .tool-versions file
-    /// asdf install <name>                     Install one tool at the version
-    ///                                         specified in the .tool-versions file
-    /// asdf install <name> <version>           Install a specific version of a package
-    /// asdf install <name> latest[:<version>]  Install the latest stable version of a
-    ///                                         package, or with optional version,
-    ///                                         install the latest stable version that
-    ///                                         begins with the given string
-    /// ```
-    pub fn install<A, B>(name: Option<A>, version: Option<B>) -> !
+    /// The version of `asdf` that this client runs, which decides the arguments and output parsing
+    /// used for each command, and whether a command is available at all.
+    ///
+    /// Unless it was set by [`Asdf::with_version`], the version is detected with `asdf --version`
+    /// the first time it is needed, and remembered by this client and its clones.
+    pub fn version(&self) -> Result<AsdfVersion> {
+        if let Some(version) = self.known_version() {
+            return Ok(version);
+        }
+
+        let version = self
+            .run(Subcommand::Version, ["--version"], None)
+            .and_then(version_output)?;
+
+        Ok(*self.detected_version.get_or_init(|| version))
+    }
+
+    /// See [`Asdf::version`].
+    #[cfg(feature = "tokio")]
+    pub async fn version_async(&self) -> Result<AsdfVersion> {
+        if let Some(version) = self.known_version() {
+            return Ok(version);
+        }
+
+        let version = self
+            .run_async(Subcommand::Version, ["--version"], None)
+            .await
+            .and_then(version_output)?;
+
+        Ok(*self.detected_version.get_or_init(|| version))
+    }
+
+    fn known_version(&self) -> Option<AsdfVersion> {
+        self.version
+            .or_else(|| self.detected_version.get().copied())
+    }
+
+    /// The directory that `asdf` stores plugins, installs and shims in, which is `ASDF_DATA_DIR`
+    /// as the commands see it (see [`Asdf::env_var`]), or `~/.asdf`.
+    fn resolved_data_dir(&self) -> Option<PathBuf> {
+        let data_dir = self
+            .env_var("ASDF_DATA_DIR")
+            .map(PathBuf::from)
+            .or_else(|| std::env::home_dir().map(|home| home.join(".asdf")))?;
+
+        Some(match &self.current_dir {
+            Some(current_dir) => current_dir.join(data_dir),
+            None => data_dir,
+        })
+    }
+
+    /// The nearest `.tool-versions` file, or the file named by [`Asdf::default_tool_versions_filename`],
+    /// in the working directory of the commands or one of its parents.
+    fn tool_versions_file(&self) -> Option<PathBuf> {
+        let filename = self
+            .env_var("ASDF_DEFAULT_TOOL_VERSIONS_FILENAME")
+            .unwrap_or_else(|| ".tool-versions".into());
+        let mut dir = std::env::current_dir().ok()?;
+        if let Some(current_dir) = &self.current_dir {
+            dir = dir.join(current_dir);
+        }
+
+        dir.ancestors()
+            .map(|dir| dir.join(&filename))
+            .find(|path| path.is_file())
+    }
+
+    /// The value of an environment variable as the commands see it, which is the last value set by this client
+    /// (see [`Asdf::command_envs`]), or the value in the current process.
+    fn env_var(&self, name: &str) -> Option<OsString> {
+        self.command_envs()
+            .filter(|(key, _)| *key == name)
+            .last()
+            .map(|(_, value)| value.to_owned())
+            .or_else(|| std::env::var_os(name))
+    }
+
+    /// Read every shim, so that the changes made by a command can be found with [`utils::ShimChanges`].
+    fn shims(&self) -> utils::Shims {
+        self.resolved_data_dir()
+            .map(|data_dir| utils::Shims::read(&data_dir.join("shims")))
+            .unwrap_or_default()
+    }
+
+    /// All of the environment variables that this client sets, in the order they are applied.
+    fn command_envs(&self) -> impl Iterator<Item = (&OsStr, &OsStr)> {
+        [
+            ("ASDF_DIR", self.dir.as_ref().map(|x| x.as_os_str())),
+            (
+                "ASDF_DATA_DIR",
+                self.data_dir.as_ref().map(|x| x.as_os_str()),
+            ),
+            (
+                "ASDF_CONFIG_FILE",
+                self.config_file.as_ref().map(|x| x.as_os_str()),
+            ),
+            (
+                "ASDF_DEFAULT_TOOL_VERSIONS_FILENAME",
+                self.default_tool_versions_filename.as_deref(),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| Some((OsStr::new(key), value?)))
+        .chain(
+            self.envs
+                .iter()
+                .map(|(key, value)| (key.as_os_str(), value.as_os_str())),
+        )
+    }
+
+    /// Build an [`Invocation`] of `asdf` with the arguments provided and the environment of this client.
+    fn invocation<S, I>(&self, args: I) -> Invocation
     where
-        A: AsRef<str>,
-        B: AsRef<str>,
+        S: AsRef<OsStr>,
+        I: IntoIterator<Item = S>,
     {
-        todo!();
+        let mut invocation = self.command_envs().fold(
+            Invocation::new(&self.binary).with_args(args),
+            |invocation, (key, value)| invocation.with_env(key, value),
+        );
+
+        if let Some(current_dir) = &self.current_dir {
+            invocation = invocation.with_current_dir(current_dir);
+        }
+        if let Some(timeout) = self.timeout {
+            invocation = invocation.with_timeout(timeout);
+        }
+        if let Some(cancel_handle) = &self.cancel_handle {
+            invocation = invocation.with_cancel_handle(cancel_handle.clone());
+        }
+
+        invocation
     }
 
-    /// ```help
-    /// asdf uninstall <name> <version>         Remove a specific version of a package
-    /// ```
-    pub fn uninstall<A, B>(name: A, version: B) -> !
+    /// Run `asdf` with the arguments provided, capturing the output. The version of `asdf` is detected first,
+    /// and [`Error::Unsupported`] is returned if it does not have `subcommand`.
+    fn command<S, I>(&self, subcommand: Subcommand, args: I) -> Result<CommandResult>
     where
-        A: AsRef<str>,
-        B: AsRef<str>,
+        S: AsRef<OsStr>,
+        I: IntoIterator<Item = S>,
     {
-        todo!();
+        let version = self.version()?;
+
+        subcommand.require(version)?;
+        self.run(subcommand, args, Some(version))
     }
 
-    /// ```help
-    /// asdf current                            Display current version set or being
-    ///                                         used for all packages
-    /// asdf current <name>                     Display current version set or being
-    ///                                         used for package
-    /// ```
-    pub fn current<A>(name: Option<A>) -> !
+    /// Run `asdf` with the arguments provided without blocking, see [`Asdf::command`].
+    #[cfg(feature = "tokio")]
+    async fn command_async<S, I>(&self, subcommand: Subcommand, args: I) -> Result<CommandResult>
     where
-        A: AsRef<str>,
+        S: AsRef<OsStr>,
+        I: IntoIterator<Item = S>,
     {
-        todo!();
+        let version = self.version_async().await?;
+
+        subcommand.require(version)?;
+        self.run_async(subcommand, args, Some(version)).await
     }
 
-    /// ```help
-    /// asdf where <name> [<version>]           Display install path for an installed
-    ///                                         or current version
-    /// ```
-    pub fn locate<A, B>(name: A, version: Option<B>) -> !
+    /// Start the process for `invocation` with the runner, streaming the output if a handler is set.
+    fn spawn(&self, invocation: &Invocation) -> std::io::Result<RunOutput> {
+        match &self.output_handler {
+            Some(OutputHandler(handler)) => self
+                .runner
+                .run_streaming(invocation, &mut |event| handler(event)),
+            None => self.runner.run(invocation),
+        }
+    }
+
+    /// Start the process for `invocation` with the runner without blocking, see [`Asdf::spawn`].
+    #[cfg(feature = "tokio")]
+    async fn spawn_async(&self, invocation: &Invocation) -> std::io::Result<RunOutput> {
+        match &self.output_handler {
+            Some(OutputHandler(handler)) => {
+                self.runner
+                    .run_streaming_async(invocation, handler.as_ref())
+                    .await
+            }
+            None => self.runner.run_async(invocation).await,
+        }
+    }
+
+    /// Run `asdf` with the arguments provided, capturing the output.
+    fn run<S, I>(
+        &self,
+        subcommand: Subcommand,
+        args: I,
+        version: Option<AsdfVersion>,
+    ) -> Result<CommandResult>
     where
-        A: AsRef<str>,
-        B: AsRef<str>,
+        S: AsRef<OsStr>,
+        I: IntoIterator<Item = S>,
     {
-        todo!();
+        let invocation = self.invocation(args);
+        let output = self.spawn(&invocation)?;
+
+        CommandResult::new(subcommand, invocation, output, version)
     }
 
-    /// ```help
-    /// asdf which <command>                    Display the path to an executable
-    /// ```
-    pub fn which<A>(command: A) -> !
+    /// Run `asdf` with the arguments provided without blocking, capturing the output.
+    #[cfg(feature = "tokio")]
+    async fn run_async<S, I>(
+        &self,
+        subcommand: Subcommand,
+        args: I,
+        version: Option<AsdfVersion>,
+    ) -> Result<CommandResult>
     where
-        A: AsRef<str>,
+        S: AsRef<OsStr>,
+        I: IntoIterator<Item = S>,
     {
-        todo!();
+        let invocation = self.invocation(args);
+        let output = self.spawn_async(&invocation).await?;
+
+        CommandResult::new(subcommand, invocation, output, version)
     }
 
-    /// ```help
-    /// asdf local <name> <version>             Set the package local version
-    /// asdf local <name> latest[:<version>]    Set the package local version to the
-    ///                                         latest provided version
-    /// ```
-    pub fn local<A, B>(name: A, version: B) -> !
+    /// Run `asdf` with the arguments provided, returning the output without decoding it.
+    /// The result is only checked for errors reported by `asdf` when the command failed.
+    fn command_raw<S, I>(
+        &self,
+        subcommand: Subcommand,
+        subject: Subject<'_>,
+        args: I,
+    ) -> Result<RunOutput>
     where
-        A: AsRef<str>,
-        B: AsRef<str>,
+        S: AsRef<OsStr>,
+        I: IntoIterator<Item = S>,
     {
-        todo!();
+        let version = self.version()?;
+
+        subcommand.require(version)?;
+
+        let invocation = self.invocation(args);
+        let output = self.spawn(&invocation)?;
+
+        raw_output(subcommand, subject, invocation, output, version)
     }
 
-    /// ```help
-    /// asdf global <name> <version>            Set the package global version
-    /// asdf global <name> latest[:<version>]   Set the package global version to the
-    ///                                         latest provided version
-    /// ```
-    pub fn global<A, B>(name: A, version: B) -> !
+    /// Run `asdf` with the arguments provided without blocking, see [`Asdf::command_raw`].
+    #[cfg(feature = "tokio")]
+    async fn command_raw_async<S, I>(
+        &self,
+        subcommand: Subcommand,
+        subject: Subject<'_>,
+        args: I,
+    ) -> Result<RunOutput>
     where
-        A: AsRef<str>,
-        B: AsRef<str>,
+        S: AsRef<OsStr>,
+        I: IntoIterator<Item = S>,
     {
-        todo!();
+        let version = self.version_async().await?;
+
+        subcommand.require(version)?;
+
+        let invocation = self.invocation(args);
+        let output = self.spawn_async(&invocation).await?;
+
+        raw_output(subcommand, subject, invocation, output, version)
+    }
+}
+
+fn raw_output(
+    subcommand: Subcommand,
+    subject: Subject<'_>,
+    invocation: Invocation,
+    output: RunOutput,
+    version: AsdfVersion,
+) -> Result<RunOutput> {
+    if output.success() {
+        return Ok(output);
+    }
+
+    let result = CommandResult::lossy(subcommand, invocation, output, Some(version))?;
+
+    match classify::classify(subject, &result) {
+        Some(error) => Err(error),
+        None => Ok(result.output),
+    }
+}
+
+fn version_output(result: CommandResult) -> Result<AsdfVersion> {
+    classify::check(Subject::default(), &result)?;
+
+    result
+        .stdout
+        .parse()
+        .map_err(|_| Error::MalformedOutput(result.failure()))
+}
+
+/// The client used by the free functions, which runs `asdf` from `PATH`.
+pub(crate) fn default_client() -> &'static Asdf {
+    static CLIENT: OnceLock<Asdf> = OnceLock::new();
+
+    CLIENT.get_or_init(Asdf::default)
+}
+
+/// The output of a command that ran to completion, see [`classify`] for how it is checked.
+#[derive(Debug)]
+pub(crate) struct CommandResult {
+    pub(crate) subcommand: Subcommand,
+    pub(crate) invocation: Invocation,
+    pub(crate) output: RunOutput,
+    pub(crate) stdout: String,
+    pub(crate) stderr: String,
+    /// The version of `asdf` that the command was run with, if it is known.
+    pub(crate) version: Option<AsdfVersion>,
+}
+
+impl CommandResult {
+    pub(crate) fn new(
+        subcommand: Subcommand,
+        invocation: Invocation,
+        output: RunOutput,
+        version: Option<AsdfVersion>,
+    ) -> Result<Self> {
+        Self::interrupted(&invocation, &output)?;
+
+        let stdout = String::from_utf8(output.stdout().clone())?;
+        let stderr = String::from_utf8(output.stderr().clone())?;
+
+        Ok(Self {
+            subcommand,
+            invocation,
+            output,
+            stdout,
+            stderr,
+            version,
+        })
+    }
+
+    /// The same as [`CommandResult::new`], but output that is not valid UTF-8 is decoded lossily,
+    /// for commands such as `asdf exec` that run something other than `asdf`.
+    pub(crate) fn lossy(
+        subcommand: Subcommand,
+        invocation: Invocation,
+        output: RunOutput,
+        version: Option<AsdfVersion>,
+    ) -> Result<Self> {
+        Self::interrupted(&invocation, &output)?;
+
+        Ok(Self {
+            subcommand,
+            stdout: String::from_utf8_lossy(output.stdout()).into_owned(),
+            stderr: String::from_utf8_lossy(output.stderr()).into_owned(),
+            invocation,
+            output,
+            version,
+        })
+    }
+
+    /// Return [`Error::TimedOut`] or [`Error::Cancelled`] if the command was killed.
+    fn interrupted(invocation: &Invocation, output: &RunOutput) -> Result<()> {
+        if let Some(interruption) = output.interrupted() {
+            let failure = Box::new(Failure::new(
+                invocation,
+                *output.code(),
+                String::from_utf8_lossy(output.stdout()).into_owned(),
+                String::from_utf8_lossy(output.stderr()).into_owned(),
+            ));
+
+            return Err(match interruption {
+                Interruption::TimedOut => Error::TimedOut(failure),
+                Interruption::Cancelled => Error::Cancelled(failure),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// The details of this command to carry in an [`enum@Error`].
+    pub(crate) fn failure(&self) -> Box<Failure> {
+        Box::new(Failure::new(
+            &self.invocation,
+            *self.output.code(),
+            self.stdout.clone(),
+            self.stderr.clone(),
+        ))
+    }
+}
+
+/// MANAGE PLUGINS
+pub mod plugins {
+    use super::*;
+    use crate::packages::InstalledVersions;
+
+    /// Structure representing an `asdf` plugin as returned from [`list`] or [`list_all`].
+    #[derive(Clone, Debug, PartialEq, Getters)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[getset(get = "pub")]
+    pub struct Plugin {
+        /// Name of the plugin as specified in the plugin repository, or by the argument when
+        /// installed from a Git clone URL.
+        name: String,
+        /// The Git clone URL for this plugin.
+        git_url: String,
+        /// The Git branch that is used for the installed version of this plugin.
+        git_branch: Option<String>,
+        /// The Git reference that is used for the installed version of this plugin.
+        git_ref: Option<String>,
+    }
+
+    pub type PluginSet = Vec<Plugin>;
+
+    /// The Git references of a plugin before and after [`update`].
+    #[derive(Clone, Debug, PartialEq, Eq, Getters)]
+    #[getset(get = "pub")]
+    pub struct PluginUpdate {
+        /// The commit that the plugin was at before the update.
+        previous_ref: Option<String>,
+        /// The commit that the plugin is at after the update.
+        current_ref: Option<String>,
+    }
+
+    impl PluginUpdate {
+        fn new(name: &str, previous: &PluginSet, current: &PluginSet) -> Self {
+            let git_ref = |plugins: &PluginSet| {
+                plugins
+                    .iter()
+                    .find(|plugin| plugin.name == name)
+                    .and_then(|plugin| plugin.git_ref.clone())
+            };
+
+            Self {
+                previous_ref: git_ref(previous),
+                current_ref: git_ref(current),
+            }
+        }
+
+        /// Whether the plugin is at a different commit than before.
+        pub fn is_changed(&self) -> bool {
+            self.previous_ref != self.current_ref
+        }
+    }
+
+    impl TryFrom<&str> for Plugin {
+        type Error = &'static str;
+
+        /// Given a [`&str`], this function splits at whitespace and places the results into the fields of this struct
+        /// in the order they are defined. The first two, `name` and `git_url` are mandatory, whereas
+        /// `git_branch` and `git_ref` are assumed to be [`None`] if the [`std::str::SplitWhitespace`]
+        /// iterator ends after `git_url`.
+        fn try_from(other: &str) -> std::result::Result<Self, Self::Error> {
+            let mut parts = other.split_whitespace();
+
+            Ok(Self {
+                name: match parts.next() {
+                    Some(name) => name.to_owned(),
+                    None => return Err("not enough iterations to retrieve field `name`"),
+                },
+                git_url: match parts.next() {
+                    Some(name) => name.to_owned(),
+                    None => return Err("not enough iterations to retrieve field `git_url`"),
+                },
+                git_branch: parts.next().map(|x| x.to_owned()),
+                git_ref: parts.next().map(|x| x.to_owned()),
+            })
+        }
+    }
+
+    impl Asdf {
+        /// `asdf plugin add`, see [`add`].
+        pub fn plugin_add<A, B>(&self, name: A, git_url: Option<B>) -> Result<()>
+        where
+            A: AsRef<str>,
+            B: AsRef<str>,
+        {
+            self.command(
+                Subcommand::PluginAdd,
+                add_args(name.as_ref(), git_url.as_ref().map(|x| x.as_ref())),
+            )
+            .and_then(|result| add_output(name.as_ref(), result))
+        }
+
+        /// `asdf plugin add`, see [`add`].
+        #[cfg(feature = "tokio")]
+        pub async fn plugin_add_async<A, B>(&self, name: A, git_url: Option<B>) -> Result<()>
+        where
+            A: AsRef<str>,
+            B: AsRef<str>,
+        {
+            self.command_async(
+                Subcommand::PluginAdd,
+                add_args(name.as_ref(), git_url.as_ref().map(|x| x.as_ref())),
+            )
+            .await
+            .and_then(|result| add_output(name.as_ref(), result))
+        }
+
+        /// `asdf plugin list --urls --refs`, see [`list`].
+        pub fn plugin_list(&self) -> Result<PluginSet> {
+            self.command(Subcommand::PluginList, LIST_ARGS)
+                .and_then(list_output)
+        }
+
+        /// `asdf plugin list --urls --refs`, see [`list`].
+        #[cfg(feature = "tokio")]
+        pub async fn plugin_list_async(&self) -> Result<PluginSet> {
+            self.command_async(Subcommand::PluginList, LIST_ARGS)
+                .await
+                .and_then(list_output)
+        }
+
+        /// `asdf plugin list all`, see [`list_all`].
+        pub fn plugin_list_all(&self) -> Result<PluginSet> {
+            self.command(Subcommand::PluginListAll, LIST_ALL_ARGS)
+                .and_then(list_all_output)
+        }
+
+        /// `asdf plugin list all`, see [`list_all`].
+        #[cfg(feature = "tokio")]
+        pub async fn plugin_list_all_async(&self) -> Result<PluginSet> {
+            self.command_async(Subcommand::PluginListAll, LIST_ALL_ARGS)
+                .await
+                .and_then(list_all_output)
+        }
+
+        /// `asdf plugin remove`, see [`remove`].
+        pub fn plugin_remove<A>(&self, name: A) -> Result<Vec<String>>
+        where
+            A: AsRef<str>,
+        {
+            let name = name.as_ref();
+            let versions = self.list(Some(name), None::<&str>)?;
+
+            self.command(Subcommand::PluginRemove, ["plugin", "remove", name])
+                .and_then(|result| remove_output(name, versions, result))
+        }
+
+        /// `asdf plugin remove`, see [`remove`].
+        #[cfg(feature = "tokio")]
+        pub async fn plugin_remove_async<A>(&self, name: A) -> Result<Vec<String>>
+        where
+            A: AsRef<str>,
+        {
+            let name = name.as_ref();
+            let versions = self.list_async(Some(name), None::<&str>).await?;
+
+            self.command_async(Subcommand::PluginRemove, ["plugin", "remove", name])
+                .await
+                .and_then(|result| remove_output(name, versions, result))
+        }
+
+        /// `asdf plugin update`, see [`update`].
+        pub fn plugin_update<A, B>(&self, name: A, git_ref: Option<B>) -> Result<PluginUpdate>
+        where
+            A: AsRef<str>,
+            B: AsRef<str>,
+        {
+            let name = name.as_ref();
+            let previous = self.plugin_list()?;
+
+            self.command(
+                Subcommand::PluginUpdate,
+                update_args(name, git_ref.as_ref().map(|x| x.as_ref())),
+            )
+            .and_then(|result| update_output(name, result))?;
+
+            Ok(PluginUpdate::new(name, &previous, &self.plugin_list()?))
+        }
+
+        /// `asdf plugin update`, see [`update`].
+        #[cfg(feature = "tokio")]
+        pub async fn plugin_update_async<A, B>(
+            &self,
+            name: A,
+            git_ref: Option<B>,
+        ) -> Result<PluginUpdate>
+        where
+            A: AsRef<str>,
+            B: AsRef<str>,
+        {
+            let name = name.as_ref();
+            let previous = self.plugin_list_async().await?;
+
+            self.command_async(
+                Subcommand::PluginUpdate,
+                update_args(name, git_ref.as_ref().map(|x| x.as_ref())),
+            )
+            .await
+            .and_then(|result| update_output(name, result))?;
+
+            Ok(PluginUpdate::new(
+                name,
+                &previous,
+                &self.plugin_list_async().await?,
+            ))
+        }
+
+        /// `asdf plugin update --all`, see [`update_all`].
+        pub fn plugin_update_all(&self) -> Result<Vec<(String, Result<PluginUpdate>)>> {
+            let previous = self.plugin_list()?;
+            let results = previous
+                .iter()
+                .map(|plugin| {
+                    let result = self
+                        .command(Subcommand::PluginUpdate, update_args(plugin.name(), None))
+                        .and_then(|result| update_output(plugin.name(), result));
+                    (plugin.name.clone(), result)
+                })
+                .collect();
+
+            Ok(update_all_output(results, &previous, &self.plugin_list()?))
+        }
+
+        /// `asdf plugin update --all`, see [`update_all`].
+        #[cfg(feature = "tokio")]
+        pub async fn plugin_update_all_async(&self) -> Result<Vec<(String, Result<PluginUpdate>)>> {
+            let previous = self.plugin_list_async().await?;
+            let mut results = Vec::new();
+
+            for plugin in &previous {
+                let result = self
+                    .command_async(Subcommand::PluginUpdate, update_args(plugin.name(), None))
+                    .await
+                    .and_then(|result| update_output(plugin.name(), result));
+                results.push((plugin.name.clone(), result));
+            }
+
+            Ok(update_all_output(
+                results,
+                &previous,
+                &self.plugin_list_async().await?,
+            ))
+        }
+    }
+
+    fn remove_output(
+        name: &str,
+        mut versions: InstalledVersions,
+        result: CommandResult,
+    ) -> Result<Vec<String>> {
+        classify::check(Subject::name(name), &result)?;
+
+        Ok(versions
+            .remove(name)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|installed| installed.version().clone())
+            .collect())
+    }
+
+    fn update_args<'a>(name: &'a str, git_ref: Option<&'a str>) -> Vec<&'a str> {
+        let mut run = vec!["plugin", "update", name];
+        run.extend(git_ref);
+        run
+    }
+
+    fn update_output(name: &str, result: CommandResult) -> Result<()> {
+        classify::check(Subject::name(name), &result)
+    }
+
+    /// Compare the plugins listed before and after every plugin was updated, so that
+    /// `asdf plugin list` runs twice rather than twice for each plugin.
+    fn update_all_output(
+        results: Vec<(String, Result<()>)>,
+        previous: &PluginSet,
+        current: &PluginSet,
+    ) -> Vec<(String, Result<PluginUpdate>)> {
+        results
+            .into_iter()
+            .map(|(name, result)| {
+                let update = result.map(|()| PluginUpdate::new(&name, previous, current));
+                (name, update)
+            })
+            .collect()
+    }
+
+    fn add_args<'a>(name: &'a str, git_url: Option<&'a str>) -> Vec<&'a str> {
+        let mut run = vec!["plugin", "add", name];
+
+        if let Some(git_url) = git_url {
+            run.extend([git_url]);
+        }
+
+        run
+    }
+
+    fn add_output(name: &str, result: CommandResult) -> Result<()> {
+        classify::check(Subject::name(name), &result)
+    }
+
+    const LIST_ARGS: [&str; 4] = ["plugin", "list", "--urls", "--refs"];
+
+    fn list_output(result: CommandResult) -> Result<PluginSet> {
+        if classify::check_empty(Subject::default(), &result)? {
+            return Ok(PluginSet::new());
+        }
+
+        result
+            .stdout
+            .lines()
+            .map(|line| line.try_into())
+            .collect::<std::result::Result<PluginSet, _>>()
+            .map_err(|_| Error::MalformedOutput(result.failure()))
+    }
+
+    const LIST_ALL_ARGS: [&str; 3] = ["plugin", "list", "all"];
+
+    fn list_all_output(result: CommandResult) -> Result<PluginSet> {
+        classify::check(Subject::default(), &result)?;
+
+        result
+            .stdout
+            .replace("initializing plugin repository...", "")
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.try_into())
+            .collect::<std::result::Result<PluginSet, _>>()
+            .map_err(|_| Error::MalformedOutput(result.failure()))
+    }
+
+    /// Add a plugin by name from the repository, or with a name by a valid Git clone URL.
+    /// See the [`asdf` plugin repository](https://github.com/asdf-vm/asdf-plugins#plugin-list)
+    /// for a complete list, or use `asdf plugin list all` or [`list_all`].
+    ///
+    /// ```help
+    /// asdf plugin add <name> [<git-url>]      Add a plugin from the plugin repo OR,
+    ///                                         add a Git repo as a plugin by
+    ///                                         specifying the name and repo url
+    /// ```
+    pub fn add<A, B>(name: A, git_url: Option<B>) -> Result<()>
+    where
+        A: AsRef<str>,
+        B: AsRef<str>,
+    {
+        default_client().plugin_add(name, git_url)
+    }
+
+    /// Returns a [`PluginSet`] of [`Plugin`] for all plugins installed.
+    /// To get an iterable of available plugins, see [`list_all`].
+    /// Unlike the command, this function will always return a [`PluginSet`] with output from `--urls` and `--refs`.
+    ///
+    /// ```help
+    /// asdf plugin list [--urls] [--refs]      List installed plugins. Optionally show
+    ///                                         git urls and git-ref
+    /// ```
+    pub fn list() -> Result<PluginSet> {
+        default_client().plugin_list()
+    }
+
+    /// ```help
+    /// asdf plugin list all                    List plugins registered on asdf-plugins
+    ///                                         repository with URLs
+    /// ```
+    pub fn list_all() -> Result<PluginSet> {
+        default_client().plugin_list_all()
+    }
+
+    /// Remove a plugin along with every version of it that is installed, returning those versions.
+    /// The versions are listed before the plugin is removed.
+    ///
+    /// ```help
+    /// asdf plugin remove <name>               Remove plugin and package versions
+    /// ```
+    pub fn remove<A>(name: A) -> Result<Vec<String>>
+    where
+        A: AsRef<str>,
+    {
+        default_client().plugin_remove(name)
+    }
+
+    /// Update a plugin to the latest commit on its default branch, or to `git_ref` if one is given,
+    /// and return the commit it was at before and after, as shown by [`list`].
+    ///
+    /// ```help
+    /// asdf plugin update <name> [<git-ref>]   Update a plugin to latest commit on
+    ///                                         default branch or a particular git-ref
+    /// ```
+    pub fn update<A, B>(name: A, git_ref: Option<B>) -> Result<PluginUpdate>
+    where
+        A: AsRef<str>,
+        B: AsRef<str>,
+    {
+        default_client().plugin_update(name, git_ref)
+    }
+
+    /// Update every plugin as [`update`] does, one after another, and return the result for each plugin by name.
+    /// A plugin that fails to update does not stop the others, only failing to list the plugins returns an error.
+    ///
+    /// ```help
+    /// asdf plugin update --all                Update all plugins to latest commit on
+    ///                                         default branch
+    /// ```
+    pub fn update_all() -> Result<Vec<(String, Result<PluginUpdate>)>> {
+        default_client().plugin_update_all()
+    }
+}
+
+/// MANAGE PACKAGES
+pub mod packages {
+    use super::*;
+    use getset::CopyGetters;
+    use std::collections::BTreeMap;
+    use utils::ShimChanges;
+
+    /// The `.tool-versions` file that [`set`] writes to.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    pub enum Scope {
+        /// The file in the current directory, which is created if it does not exist.
+        /// Before `asdf` 0.16.0, this is `asdf local`.
+        #[default]
+        Current,
+        /// The closest file in the current directory or any of its parents.
+        /// Before `asdf` 0.16.0, this is `asdf local --parent`.
+        Parent,
+        /// The file in the home directory. Before `asdf` 0.16.0, this is `asdf global`.
+        Home,
+    }
+
+    /// The result of removing a version with [`uninstall`].
+    #[derive(Clone, Debug, Default, PartialEq, Eq, Getters, CopyGetters)]
+    pub struct Uninstallation {
+        /// Whether the install directory of the version is gone. This is only [`false`] when `asdf`
+        /// succeeded but left the directory behind, such as when it could not be deleted.
+        #[getset(get_copy = "pub")]
+        removed: bool,
+        /// The shims that were removed because only this version provided them, and those that
+        /// were rewritten because other versions still provide them.
+        #[getset(get = "pub")]
+        shims: ShimChanges,
+    }
+
+    /// The version of a tool that applies to the working directory, as returned from [`current`].
+    #[derive(Clone, Debug, PartialEq, Eq, Getters)]
+    #[getset(get = "pub")]
+    pub struct CurrentVersion {
+        /// The name of the plugin.
+        name: String,
+        /// The versions that are set, the first of which is used. This is empty when no version is set.
+        versions: Vec<String>,
+        /// Where the versions are set, if `asdf` reports it.
+        /// Before 0.16.0, this is not shown for versions that are not installed.
+        source: Option<VersionSource>,
+        /// Whether the version is installed.
+        status: VersionStatus,
+    }
+
+    /// Where the version of a tool is set, see [`CurrentVersion::source`].
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    pub enum VersionSource {
+        /// A `.tool-versions` file, or a legacy version file such as `.nvmrc`.
+        File(PathBuf),
+        /// An environment variable such as `ASDF_NODEJS_VERSION`, see [`shell`].
+        EnvVar(String),
+    }
+
+    /// Whether the version of a tool that is set is installed, see [`CurrentVersion::status`].
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub enum VersionStatus {
+        /// The version is installed and in use.
+        Installed,
+        /// The version is set, but has to be installed with [`install`] before it can be used.
+        NotInstalled,
+        /// No version is set for the working directory.
+        NoVersionSet,
+    }
+
+    /// The environment variable that `asdf shell` sets to choose the version of a tool, as returned from [`shell`].
+    ///
+    /// It can be set for a child process with [`ShellOverride::apply`], for every command run by a client
+    /// with [`Asdf::with_env`], or in an interactive shell by evaluating [`ShellOverride::to_shell`].
+    #[derive(Clone, Debug, PartialEq, Eq, Hash, Getters)]
+    #[getset(get = "pub")]
+    pub struct ShellOverride {
+        /// The name of the variable, such as `ASDF_NODEJS_VERSION`.
+        name: String,
+        /// The version to set, or [`None`] to unset the variable as `asdf shell <name> --unset` does.
+        value: Option<String>,
+    }
+
+    /// A shell that [`ShellOverride::to_shell`] can write code for.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub enum Shell {
+        /// Bash, which is written `export NAME=value`.
+        Bash,
+        /// Zsh, which is written the same as [`Shell::Bash`].
+        Zsh,
+        /// Fish, which is written `set -gx NAME value`.
+        Fish,
+    }
+
+    impl ShellOverride {
+        /// The override for the tool `tool`, which sets `version`, or unsets the variable if [`None`].
+        ///
+        /// The variable is named as `asdf` does, with the name of the tool in uppercase
+        /// and hyphens replaced by underscores, so `nodejs-lts` is `ASDF_NODEJS_LTS_VERSION`.
+        pub fn new<A, B>(tool: A, version: Option<B>) -> Self
+        where
+            A: AsRef<str>,
+            B: AsRef<str>,
+        {
+            Self {
+                name: format!(
+                    "ASDF_{}_VERSION",
+                    tool.as_ref().replace('-', "_").to_uppercase()
+                ),
+                value: version.map(|x| x.as_ref().to_owned()),
+            }
+        }
+
+        /// Set or remove the variable for `command`.
+        pub fn apply<'a>(
+            &self,
+            command: &'a mut std::process::Command,
+        ) -> &'a mut std::process::Command {
+            match &self.value {
+                Some(value) => command.env(&self.name, value),
+                None => command.env_remove(&self.name),
+            }
+        }
+
+        /// Code that sets or unsets the variable when evaluated by `shell`, such as `export ASDF_NODEJS_VERSION=20.1.0`.
+        pub fn to_shell(&self, shell: Shell) -> String {
+            match (shell, &self.value) {
+                (Shell::Bash | Shell::Zsh, Some(value)) => {
+                    format!("export {}={}", self.name, shell_escape(value))
+                }
+                (Shell::Bash | Shell::Zsh, None) => format!("unset {}", self.name),
+                (Shell::Fish, Some(value)) => format!(
+                    "set -gx {} '{}'",
+                    self.name,
+                    value.replace('\\', r"\\").replace('\'', r"\'")
+                ),
+                (Shell::Fish, None) => format!("set -e {}", self.name),
+            }
+        }
+    }
+
+    /// The latest stable version of a plugin, as returned from [`latest_all`].
+    #[derive(Clone, Debug, PartialEq, Eq, Getters)]
+    #[getset(get = "pub")]
+    pub struct LatestVersion {
+        /// The name of the plugin.
+        name: String,
+        /// The latest version, and whether it is installed.
+        state: LatestState,
+    }
+
+    impl LatestVersion {
+        /// The latest version, unless it could not be found.
+        pub fn version(&self) -> Option<&str> {
+            match &self.state {
+                LatestState::Installed(version) | LatestState::Missing(version) => Some(version),
+                LatestState::Unknown => None,
+            }
+        }
+    }
+
+    /// Whether the latest version of a plugin is installed, see [`LatestVersion::state`].
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    pub enum LatestState {
+        /// The latest version is installed.
+        Installed(String),
+        /// The latest version is not installed.
+        Missing(String),
+        /// The plugin could not find its latest version, usually because its `latest-stable`
+        /// or `list-all` script failed.
+        Unknown,
+    }
+
+    /// An installed version of a tool, as returned from [`list`].
+    #[derive(Clone, Debug, PartialEq, Eq, Hash, Getters, CopyGetters)]
+    pub struct InstalledVersion {
+        /// The version.
+        #[getset(get = "pub")]
+        version: String,
+        /// Whether this is the version set for the working directory, which `asdf` marks with `*`.
+        #[getset(get_copy = "pub")]
+        current: bool,
+    }
+
+    /// The installed versions of each plugin, by name, as returned from [`list`].
+    pub type InstalledVersions = BTreeMap<String, Vec<InstalledVersion>>;
+
+    /// What [`install`] installs.
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    pub enum InstallSpec {
+        /// Every tool with a version set for the working directory, as `asdf install`.
+        All,
+        /// One tool at the version set for the working directory, as `asdf install <name>`.
+        Tool(String),
+        /// One tool at an exact version, as `asdf install <name> <version>`.
+        Version { name: String, version: String },
+        /// The latest stable version of one tool, or the latest that begins with `prefix`,
+        /// as `asdf install <name> latest[:<version>]`.
+        Latest {
+            name: String,
+            prefix: Option<String>,
+        },
+    }
+
+    impl InstallSpec {
+        /// The tool and the version argument for `asdf install`, or [`None`] for [`InstallSpec::All`].
+        fn args(&self) -> Option<(&str, Option<String>)> {
+            match self {
+                Self::All => None,
+                Self::Tool(name) => Some((name, None)),
+                Self::Version { name, version } => Some((name, Some(version.clone()))),
+                Self::Latest { name, prefix } => Some((
+                    name,
+                    Some(match prefix {
+                        Some(prefix) => format!("latest:{prefix}"),
+                        None => "latest".to_owned(),
+                    }),
+                )),
+            }
+        }
+    }
+
+    /// The result of installing one tool with [`install`].
+    #[derive(Clone, Debug, PartialEq, Eq, Getters)]
+    #[getset(get = "pub")]
+    pub struct Installation {
+        /// The name of the plugin.
+        name: String,
+        /// The version that was installed, if it was given exactly or reported by `asdf`.
+        version: Option<String>,
+        /// Whether the version was installed.
+        outcome: InstallOutcome,
+    }
+
+    /// Whether a tool was installed, see [`Installation`].
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub enum InstallOutcome {
+        /// The version was downloaded and installed.
+        Installed,
+        /// The version was installed before, and nothing was done.
+        AlreadyInstalled,
+        /// The plugin failed to download or build the version. The [`Failure`] holds
+        /// everything that the plugin printed, which is usually a build log.
+        Failed(Box<Failure>),
+    }
+
+    impl Asdf {
+        /// `asdf install`, see [`install`].
+        pub fn install(&self, spec: InstallSpec) -> Result<Vec<(String, Result<Installation>)>> {
+            let Some((name, version)) = spec.args() else {
+                return Ok(self
+                    .tools_to_install(self.current(None::<&str>)?)
+                    .into_iter()
+                    .flat_map(|(name, versions)| {
+                        let installations = self.install_one(&name, None, &versions);
+                        by_tool(name, installations)
+                    })
+                    .collect());
+            };
+            let versions = match version {
+                Some(_) => Vec::new(),
+                None => set_versions(self.current(Some(name))?),
+            };
+
+            Ok(by_tool(
+                name.to_owned(),
+                Ok(self.install_one(name, version.as_deref(), &versions)?),
+            ))
+        }
+
+        /// `asdf install`, see [`install`].
+        #[cfg(feature = "tokio")]
+        pub async fn install_async(
+            &self,
+            spec: InstallSpec,
+        ) -> Result<Vec<(String, Result<Installation>)>> {
+            let Some((name, version)) = spec.args() else {
+                let mut installations = Vec::new();
+
+                for (name, versions) in
+                    self.tools_to_install(self.current_async(None::<&str>).await?)
+                {
+                    let installation = self.install_one_async(&name, None, &versions).await;
+                    installations.extend(by_tool(name, installation));
+                }
+
+                return Ok(installations);
+            };
+            let versions = match version {
+                Some(_) => Vec::new(),
+                None => set_versions(self.current_async(Some(name)).await?),
+            };
+
+            Ok(by_tool(
+                name.to_owned(),
+                Ok(self
+                    .install_one_async(name, version.as_deref(), &versions)
+                    .await?),
+            ))
+        }
+
+        /// The tools that `asdf install` installs and the versions set for each, which are those
+        /// in the nearest `.tool-versions` in order, whether or not their plugin is installed, followed by those
+        /// that `current` reports a version for from elsewhere, such as a legacy version file or an environment variable.
+        /// The versions reported by `current` are used where there are any, because they include those overrides.
+        fn tools_to_install(&self, current: Vec<CurrentVersion>) -> Vec<(String, Vec<String>)> {
+            let mut tools = self
+                .tool_versions_file()
+                .and_then(|path| std::fs::read_to_string(path).ok())
+                .map(|contents| tool_versions_lines(&contents))
+                .unwrap_or_default();
+
+            for current in current.into_iter().filter(|x| !x.versions.is_empty()) {
+                match tools.iter_mut().find(|(name, _)| *name == current.name) {
+                    Some((_, versions)) => *versions = current.versions,
+                    None => tools.push((current.name, current.versions)),
+                }
+            }
+            tools
+        }
+
+        fn install_one(
+            &self,
+            name: &str,
+            version: Option<&str>,
+            versions: &[String],
+        ) -> Result<Vec<Installation>> {
+            self.command(Subcommand::Install, install_args(name, version))
+                .and_then(|result| install_output(name, version, versions, result))
+        }
+
+        #[cfg(feature = "tokio")]
+        async fn install_one_async(
+            &self,
+            name: &str,
+            version: Option<&str>,
+            versions: &[String],
+        ) -> Result<Vec<Installation>> {
+            self.command_async(Subcommand::Install, install_args(name, version))
+                .await
+                .and_then(|result| install_output(name, version, versions, result))
+        }
+
+        /// `asdf uninstall`, see [`uninstall`].
+        pub fn uninstall<A, B>(&self, name: A, version: B) -> Result<Uninstallation>
+        where
+            A: AsRef<str>,
+            B: AsRef<str>,
+        {
+            let (name, version) = (name.as_ref(), version.as_ref());
+            let shims = self.shims();
+
+            self.command(Subcommand::Uninstall, ["uninstall", name, version])
+                .and_then(|result| self.uninstall_output(name, version, shims, result))
+        }
+
+        /// `asdf uninstall`, see [`uninstall`].
+        #[cfg(feature = "tokio")]
+        pub async fn uninstall_async<A, B>(&self, name: A, version: B) -> Result<Uninstallation>
+        where
+            A: AsRef<str>,
+            B: AsRef<str>,
+        {
+            let (name, version) = (name.as_ref(), version.as_ref());
+            let shims = self.shims();
+
+            self.command_async(Subcommand::Uninstall, ["uninstall", name, version])
+                .await
+                .and_then(|result| self.uninstall_output(name, version, shims, result))
+        }
+
+        fn uninstall_output(
+            &self,
+            name: &str,
+            version: &str,
+            before: utils::Shims,
+            result: CommandResult,
+        ) -> Result<Uninstallation> {
+            let subject = Subject {
+                name: Some(name),
+                version: Some(version),
+            };
+            classify::check(subject, &result)?;
+
+            let install_path = self
+                .resolved_data_dir()
+                .map(|data_dir| data_dir.join("installs").join(name).join(version));
+
+            Ok(Uninstallation {
+                removed: install_path.is_none_or(|path| !path.exists()),
+                shims: ShimChanges::between(&before, &self.shims()),
+            })
+        }
+
+        /// `asdf current`, see [`current`].
+        pub fn current<A>(&self, name: Option<A>) -> Result<Vec<CurrentVersion>>
+        where
+            A: AsRef<str>,
+        {
+            let name = name.as_ref().map(|x| x.as_ref());
+
+            self.command(Subcommand::Current, current_args(name))
+                .and_then(|result| current_output(name, result))
+        }
+
+        /// `asdf current`, see [`current`].
+        #[cfg(feature = "tokio")]
+        pub async fn current_async<A>(&self, name: Option<A>) -> Result<Vec<CurrentVersion>>
+        where
+            A: AsRef<str>,
+        {
+            let name = name.as_ref().map(|x| x.as_ref());
+
+            self.command_async(Subcommand::Current, current_args(name))
+                .await
+                .and_then(|result| current_output(name, result))
+        }
+
+        /// `asdf where`, see [`locate`].
+        pub fn locate<A, B>(&self, name: A, version: Option<B>) -> Result<PathBuf>
+        where
+            A: AsRef<str>,
+            B: AsRef<str>,
+        {
+            let (name, version) = (name.as_ref(), version.as_ref().map(|x| x.as_ref()));
+
+            self.command(Subcommand::Where, locate_args(name, version))
+                .and_then(|result| locate_output(name, version, result))
+        }
+
+        /// `asdf where`, see [`locate`].
+        #[cfg(feature = "tokio")]
+        pub async fn locate_async<A, B>(&self, name: A, version: Option<B>) -> Result<PathBuf>
+        where
+            A: AsRef<str>,
+            B: AsRef<str>,
+        {
+            let (name, version) = (name.as_ref(), version.as_ref().map(|x| x.as_ref()));
+
+            self.command_async(Subcommand::Where, locate_args(name, version))
+                .await
+                .and_then(|result| locate_output(name, version, result))
+        }
+
+        /// `asdf which`, see [`which`].
+        pub fn which<A>(&self, command: A) -> Result<PathBuf>
+        where
+            A: AsRef<str>,
+        {
+            let command = command.as_ref();
+
+            self.command(Subcommand::Which, ["which", command])
+                .and_then(|result| which_output(command, result))
+        }
+
+        /// `asdf which`, see [`which`].
+        #[cfg(feature = "tokio")]
+        pub async fn which_async<A>(&self, command: A) -> Result<PathBuf>
+        where
+            A: AsRef<str>,
+        {
+            let command = command.as_ref();
+
+            self.command_async(Subcommand::Which, ["which", command])
+                .await
+                .and_then(|result| which_output(command, result))
+        }
+
+        /// `asdf set`, see [`set`].
+        pub fn set<A, B, C>(&self, scope: Scope, name: A, versions: C) -> Result<()>
+        where
+            A: AsRef<str>,
+            B: AsRef<str>,
+            C: IntoIterator<Item = B>,
+        {
+            let versions = versions.into_iter().collect::<Vec<_>>();
+            let (subcommand, args) = set_args(
+                self.version()?,
+                scope,
+                name.as_ref(),
+                versions.iter().map(|x| x.as_ref()),
+            );
+
+            self.command(subcommand, args)
+                .and_then(|result| set_output(name.as_ref(), result))
+        }
+
+        /// `asdf set`, see [`set`].
+        #[cfg(feature = "tokio")]
+        pub async fn set_async<A, B, C>(&self, scope: Scope, name: A, versions: C) -> Result<()>
+        where
+            A: AsRef<str>,
+            B: AsRef<str>,
+            C: IntoIterator<Item = B>,
+        {
+            let versions = versions.into_iter().collect::<Vec<_>>();
+            let (subcommand, args) = set_args(
+                self.version_async().await?,
+                scope,
+                name.as_ref(),
+                versions.iter().map(|x| x.as_ref()),
+            );
+
+            self.command_async(subcommand, args)
+                .await
+                .and_then(|result| set_output(name.as_ref(), result))
+        }
+
+        /// `asdf local`, see [`local`].
+        pub fn local<A, B>(&self, name: A, version: B) -> Result<()>
+        where
+            A: AsRef<str>,
+            B: AsRef<str>,
+        {
+            self.set(Scope::Current, name, [version])
+        }
+
+        /// `asdf local`, see [`local`].
+        #[cfg(feature = "tokio")]
+        pub async fn local_async<A, B>(&self, name: A, version: B) -> Result<()>
+        where
+            A: AsRef<str>,
+            B: AsRef<str>,
+        {
+            self.set_async(Scope::Current, name, [version]).await
+        }
+
+        /// `asdf global`, see [`global`].
+        pub fn global<A, B>(&self, name: A, version: B) -> Result<()>
+        where
+            A: AsRef<str>,
+            B: AsRef<str>,
+        {
+            self.set(Scope::Home, name, [version])
+        }
+
+        /// `asdf global`, see [`global`].
+        #[cfg(feature = "tokio")]
+        pub async fn global_async<A, B>(&self, name: A, version: B) -> Result<()>
+        where
+            A: AsRef<str>,
+            B: AsRef<str>,
+        {
+            self.set_async(Scope::Home, name, [version]).await
+        }
+
+        /// `asdf shell`, see [`shell`].
+        pub fn shell<A, B>(&self, name: A, version: B) -> ShellOverride
+        where
+            A: AsRef<str>,
+            B: AsRef<str>,
+        {
+            ShellOverride::new(name, Some(version))
+        }
+
+        /// `asdf latest`, see [`latest`].
+        pub fn latest<A, B>(&self, name: A, prefix: Option<B>) -> Result<String>
+        where
+            A: AsRef<str>,
+            B: AsRef<str>,
+        {
+            let (name, prefix) = (name.as_ref(), prefix.as_ref().map(|x| x.as_ref()));
+
+            self.command(Subcommand::Latest, latest_args(name, prefix))
+                .and_then(|result| latest_output(name, prefix, result))
+        }
+
+        /// `asdf latest`, see [`latest`].
+        #[cfg(feature = "tokio")]
+        pub async fn latest_async<A, B>(&self, name: A, prefix: Option<B>) -> Result<String>
+        where
+            A: AsRef<str>,
+            B: AsRef<str>,
+        {
+            let (name, prefix) = (name.as_ref(), prefix.as_ref().map(|x| x.as_ref()));
+
+            self.command_async(Subcommand::Latest, latest_args(name, prefix))
+                .await
+                .and_then(|result| latest_output(name, prefix, result))
+        }
+
+        /// `asdf latest --all`, see [`latest_all`].
+        pub fn latest_all(&self) -> Result<Vec<LatestVersion>> {
+            self.command(Subcommand::LatestAll, LATEST_ALL_ARGS)
+                .and_then(latest_all_output)
+        }
+
+        /// `asdf latest --all`, see [`latest_all`].
+        #[cfg(feature = "tokio")]
+        pub async fn latest_all_async(&self) -> Result<Vec<LatestVersion>> {
+            self.command_async(Subcommand::LatestAll, LATEST_ALL_ARGS)
+                .await
+                .and_then(latest_all_output)
+        }
+
+        /// `asdf list`, see [`list`].
+        pub fn list<A, B>(&self, name: Option<A>, filter: Option<B>) -> Result<InstalledVersions>
+        where
+            A: AsRef<str>,
+            B: AsRef<str>,
+        {
+            let (name, filter) = (
+                name.as_ref().map(|x| x.as_ref()),
+                filter.as_ref().map(|x| x.as_ref()),
+            );
+
+            self.command(Subcommand::List, list_args(name, filter))
+                .and_then(|result| list_output(name, filter, result))
+        }
+
+        /// `asdf list`, see [`list`].
+        #[cfg(feature = "tokio")]
+        pub async fn list_async<A, B>(
+            &self,
+            name: Option<A>,
+            filter: Option<B>,
+        ) -> Result<InstalledVersions>
+        where
+            A: AsRef<str>,
+            B: AsRef<str>,
+        {
+            let (name, filter) = (
+                name.as_ref().map(|x| x.as_ref()),
+                filter.as_ref().map(|x| x.as_ref()),
+            );
+
+            self.command_async(Subcommand::List, list_args(name, filter))
+                .await
+                .and_then(|result| list_output(name, filter, result))
+        }
+
+        /// `asdf list all`, see [`list_all`].
+        pub fn list_all<A, B>(&self, name: A, prefix: Option<B>) -> Result<Vec<String>>
+        where
+            A: AsRef<str>,
+            B: AsRef<str>,
+        {
+            let (name, prefix) = (name.as_ref(), prefix.as_ref().map(|x| x.as_ref()));
+
+            self.command(Subcommand::ListAll, list_all_args(name, prefix))
+                .and_then(|result| list_all_output(name, prefix, result))
+        }
+
+        /// `asdf list all`, see [`list_all`].
+        #[cfg(feature = "tokio")]
+        pub async fn list_all_async<A, B>(&self, name: A, prefix: Option<B>) -> Result<Vec<String>>
+        where
+            A: AsRef<str>,
+            B: AsRef<str>,
+        {
+            let (name, prefix) = (name.as_ref(), prefix.as_ref().map(|x| x.as_ref()));
+
+            self.command_async(Subcommand::ListAll, list_all_args(name, prefix))
+                .await
+                .and_then(|result| list_all_output(name, prefix, result))
+        }
+    }
+
+    fn install_args<'a>(name: &'a str, version: Option<&'a str>) -> Vec<&'a str> {
+        let mut run = vec!["install", name];
+        run.extend(version);
+        run
+    }
+
+    /// Parse the output of `asdf install`. `versions` are those set for the tool when no version was given,
+    /// each of which is installed, and get an [`Installation`] of their own.
+    fn install_output(
+        name: &str,
+        version: Option<&str>,
+        versions: &[String],
+        result: CommandResult,
+    ) -> Result<Vec<Installation>> {
+        let subject = Subject {
+            name: Some(name),
+            version,
+        };
+        let outcome = match classify::check(subject, &result) {
+            Ok(()) => InstallOutcome::Installed,
+            Err(Error::FailedCommand(failure)) => InstallOutcome::Failed(failure),
+            Err(error) => return Err(error),
+        };
+        let already = already_installed(name, &result);
+        let installation = |version: Option<&str>| Installation {
+            name: name.to_owned(),
+            version: version.map(str::to_owned),
+            outcome: match version {
+                Some(version) if already.contains(&version) => InstallOutcome::AlreadyInstalled,
+                _ => outcome.clone(),
+            },
+        };
+
+        if !versions.is_empty() {
+            return Ok(versions
+                .iter()
+                .map(|version| installation(Some(version)))
+                .collect());
+        }
+        // The version is unknown for `latest`, unless `asdf` reports that it is already installed.
+        Ok(
+            match (
+                version.filter(|x| !x.starts_with("latest")),
+                already.first(),
+            ) {
+                (Some(version), _) => vec![installation(Some(version))],
+                (None, Some(installed)) => vec![installation(Some(installed))],
+                (None, None) => vec![installation(None)],
+            },
+        )
+    }
+
+    /// The versions in the messages that `asdf` prints for those already installed, which are
+    /// `<name> <version> is already installed` before 0.16.0 and `version <version> of <name> is already installed` after.
+    fn already_installed<'a>(name: &str, result: &'a CommandResult) -> Vec<&'a str> {
+        result
+            .stdout
+            .lines()
+            .chain(result.stderr.lines())
+            .filter_map(|line| {
+                let line = line.trim().strip_suffix("is already installed")?.trim_end();
+
+                match line.strip_prefix("version ") {
+                    Some(line) => line.strip_suffix(name)?.trim_end().strip_suffix(" of"),
+                    None => line.strip_prefix(name)?.strip_prefix(' '),
+                }
+            })
+            .collect()
+    }
+
+    /// One entry for each version of the tool `name` that was installed, or the error for all of them.
+    fn by_tool(
+        name: String,
+        installations: Result<Vec<Installation>>,
+    ) -> Vec<(String, Result<Installation>)> {
+        match installations {
+            Ok(installations) => installations
+                .into_iter()
+                .map(|installation| (name.clone(), Ok(installation)))
+                .collect(),
+            Err(error) => vec![(name, Err(error))],
+        }
+    }
+
+    /// The versions set for the one tool that `current` was run for.
+    fn set_versions(current: Vec<CurrentVersion>) -> Vec<String> {
+        current
+            .into_iter()
+            .next()
+            .map(|current| current.versions)
+            .unwrap_or_default()
+    }
+
+    /// The name and versions on each line of a `.tool-versions` file, skipping blank lines and comments.
+    fn tool_versions_lines(contents: &str) -> Vec<(String, Vec<String>)> {
+        contents
+            .lines()
+            .filter_map(|line| {
+                let mut words = line
+                    .split('#')
+                    .next()?
+                    .split_whitespace()
+                    .map(str::to_owned);
+
+                Some((words.next()?, words.collect()))
+            })
+            .collect()
+    }
+
+    fn current_args(name: Option<&str>) -> Vec<&str> {
+        let mut run = vec!["current"];
+        run.extend(name);
+        run
+    }
+
+    /// The version that `asdf` shows in place of a version or source that is not set.
+    const UNSET: &str = "______";
+
+    fn current_output(name: Option<&str>, result: CommandResult) -> Result<Vec<CurrentVersion>> {
+        let subject = Subject {
+            name,
+            version: None,
+        };
+        // A tool without a version installed is shown with a failing exit code, which is not an error here.
+        if let Some(error) = classify::classify(subject, &result) {
+            return Err(error);
+        }
+        if !result.output.success() && result.stdout.trim().is_empty() {
+            return Err(Error::FailedCommand(result.failure()));
+        }
+
+        let lines = result.stdout.lines().filter(|line| !line.trim().is_empty());
+        let header = lines.clone().next();
+
+        let versions: Option<Vec<_>> = match header.and_then(TableColumns::from_header) {
+            Some(columns) => lines.skip(1).map(|line| columns.parse(line)).collect(),
+            None => lines.map(parse_current_line).collect(),
+        };
+
+        versions.ok_or_else(|| Error::MalformedOutput(result.failure()))
+    }
+
+    /// Parse a line printed by `asdf current` before 0.16.0, where the last column describes the version:
+    ///
+    /// ```text
+    /// nodejs          20.1.0          /home/user/project/.tool-versions
+    /// python          3.12.0          Not installed. Run "asdf install python 3.12.0"
+    /// rust            ______          No version is set. Run "asdf <global|shell|local> rust <version>"
+    /// ```
+    fn parse_current_line(line: &str) -> Option<CurrentVersion> {
+        let mut words = line
+            .split_whitespace()
+            .map(|word| (word.as_ptr() as usize - line.as_ptr() as usize, word));
+        let (_, name) = words.next()?;
+        // The description is a path, an environment variable, or a sentence, none of which look like a version.
+        let (start, _) = words.clone().find(|(_, word)| {
+            ["/", "~", "ASDF_"].iter().any(|x| word.starts_with(x)) || ["Not", "No"].contains(word)
+        })?;
+        let versions = words
+            .take_while(|(offset, _)| *offset < start)
+            .map(|(_, word)| word)
+            .filter(|word| *word != UNSET)
+            .map(str::to_owned)
+            .collect();
+        let description = line[start..].trim();
+
+        let (source, status) = if description.starts_with("Not installed") {
+            (None, VersionStatus::NotInstalled)
+        } else if description.starts_with("No version") {
+            (None, VersionStatus::NoVersionSet)
+        } else {
+            (parse_source(description), VersionStatus::Installed)
+        };
+
+        Some(CurrentVersion {
+            name: name.to_owned(),
+            versions,
+            source,
+            status,
+        })
+    }
+
+    /// Where each column begins in the table printed by `asdf current` since 0.16.0:
+    ///
+    /// ```text
+    /// Name            Version         Source                          Installed
+    /// nodejs          20.1.0          /home/user/project/.tool-versions true
+    /// rust            ______          ______                          false
+    /// ```
+    struct TableColumns {
+        version: usize,
+        source: usize,
+    }
+
+    impl TableColumns {
+        fn from_header(header: &str) -> Option<Self> {
+            if !header.starts_with("Name") || !header.contains("Installed") {
+                return None;
+            }
+
+            Some(Self {
+                version: header.find("Version")?,
+                source: header.find("Source")?,
+            })
+        }
+
+        fn parse(&self, line: &str) -> Option<CurrentVersion> {
+            let (rest, installed) = line.trim_end().rsplit_once(char::is_whitespace)?;
+            let installed = installed.parse::<bool>().ok()?;
+            let name = rest.get(..self.version)?.trim();
+            let versions = rest
+                .get(self.version..self.source)?
+                .split_whitespace()
+                .filter(|word| *word != UNSET)
+                .map(str::to_owned)
+                .collect::<Vec<_>>();
+            let source = rest.get(self.source..).unwrap_or_default().trim();
+
+            let status = match (installed, versions.is_empty()) {
+                (true, _) => VersionStatus::Installed,
+                (false, true) => VersionStatus::NoVersionSet,
+                (false, false) => VersionStatus::NotInstalled,
+            };
+
+            Some(CurrentVersion {
+                name: name.to_owned(),
+                versions,
+                source: parse_source(source),
+                status,
+            })
+        }
+    }
+
+    fn parse_source(source: &str) -> Option<VersionSource> {
+        match source {
+            "" | UNSET => None,
+            _ if source.starts_with("ASDF_") => source
+                .split_whitespace()
+                .next()
+                .map(|name| VersionSource::EnvVar(name.to_owned())),
+            _ => Some(VersionSource::File(PathBuf::from(source))),
+        }
+    }
+
+    fn locate_args<'a>(name: &'a str, version: Option<&'a str>) -> Vec<&'a str> {
+        let mut run = vec!["where", name];
+        run.extend(version);
+        run
+    }
+
+    fn locate_output(name: &str, version: Option<&str>, result: CommandResult) -> Result<PathBuf> {
+        classify::check(
+            Subject {
+                name: Some(name),
+                version,
+            },
+            &result,
+        )?;
+
+        match result.stdout.trim() {
+            "" => Err(Error::MalformedOutput(result.failure())),
+            path => Ok(PathBuf::from(path)),
+        }
+    }
+
+    fn which_output(command: &str, result: CommandResult) -> Result<PathBuf> {
+        classify::check(Subject::name(command), &result)?;
+
+        match result.stdout.trim() {
+            "" => Err(Error::MalformedOutput(result.failure())),
+            path => Ok(PathBuf::from(path)),
+        }
+    }
+
+    fn latest_args<'a>(name: &'a str, prefix: Option<&'a str>) -> Vec<&'a str> {
+        let mut run = vec!["latest", name];
+        run.extend(prefix);
+        run
+    }
+
+    fn latest_output(name: &str, prefix: Option<&str>, result: CommandResult) -> Result<String> {
+        classify::check(
+            Subject {
+                name: Some(name),
+                version: Some(prefix.unwrap_or("latest")),
+            },
+            &result,
+        )?;
+
+        match result.stdout.trim() {
+            "" => Err(Error::MalformedOutput(result.failure())),
+            version => Ok(version.to_owned()),
+        }
+    }
+
+    const LATEST_ALL_ARGS: [&str; 2] = ["latest", "--all"];
+
+    /// Parse the lines printed by `asdf latest --all`, which are `<name> <version> <installed|missing>`,
+    /// with `unknown` in place of the version when it could not be found.
+    fn latest_all_output(result: CommandResult) -> Result<Vec<LatestVersion>> {
+        classify::check(Subject::default(), &result)?;
+
+        result
+            .stdout
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let mut words = line.split_whitespace();
+                let (name, version, installed) = (words.next()?, words.next()?, words.next()?);
+                let state = match (version, installed) {
+                    ("unknown", _) => LatestState::Unknown,
+                    (version, "installed") => LatestState::Installed(version.to_owned()),
+                    (version, "missing") => LatestState::Missing(version.to_owned()),
+                    _ => return None,
+                };
+
+                Some(LatestVersion {
+                    name: name.to_owned(),
+                    state,
+                })
+            })
+            .collect::<Option<_>>()
+            .ok_or_else(|| Error::MalformedOutput(result.failure()))
+    }
+
+    fn list_args<'a>(name: Option<&'a str>, filter: Option<&'a str>) -> Vec<&'a str> {
+        let mut run = vec!["list"];
+        // `asdf list` only takes a filter after a name, so without one it is applied to the output instead.
+        if let Some(name) = name {
+            run.push(name);
+            run.extend(filter);
+        }
+        run
+    }
+
+    /// Parse the output of `asdf list`, where each version is indented below the name of its plugin,
+    /// or without the names when a plugin is given. The message for a plugin without versions is printed
+    /// to standard error, but may also be found among the versions:
+    ///
+    /// ```text
+    /// nodejs
+    ///   18.0.0
+    ///  *20.1.0
+    /// rust
+    ///   No versions installed
+    /// ```
+    fn list_output(
+        name: Option<&str>,
+        filter: Option<&str>,
+        result: CommandResult,
+    ) -> Result<InstalledVersions> {
+        let subject = Subject {
+            name,
+            version: filter,
+        };
+        // No plugins, a plugin without versions, or without versions that match, is not an error here.
+        classify::check_empty(subject, &result)?;
+
+        let mut versions = InstalledVersions::new();
+        let mut plugin = name.map(|name| versions.entry(name.to_owned()).or_default());
+
+        for line in result.stdout.lines().filter(|line| {
+            !line.trim().is_empty() && !classify::is_empty_message(Subcommand::List, line)
+        }) {
+            if !line.starts_with(char::is_whitespace) {
+                plugin = Some(versions.entry(line.trim().to_owned()).or_default());
+                continue;
+            }
+
+            let version = line.trim();
+            let Some(plugin) = plugin.as_mut() else {
+                return Err(Error::MalformedOutput(result.failure()));
+            };
+            let (version, current) = match version.strip_prefix('*') {
+                Some(version) => (version, true),
+                None => (version, false),
+            };
+
+            if name.is_some() || filter.is_none_or(|filter| version.starts_with(filter)) {
+                plugin.push(InstalledVersion {
+                    version: version.to_owned(),
+                    current,
+                });
+            }
+        }
+
+        Ok(versions)
+    }
+
+    fn list_all_args<'a>(name: &'a str, prefix: Option<&'a str>) -> Vec<&'a str> {
+        let mut run = vec!["list", "all", name];
+        run.extend(prefix);
+        run
+    }
+
+    fn list_all_output(
+        name: &str,
+        prefix: Option<&str>,
+        result: CommandResult,
+    ) -> Result<Vec<String>> {
+        let subject = Subject {
+            name: Some(name),
+            version: prefix,
+        };
+
+        match classify::check(subject, &result) {
+            // No version begins with the prefix, which is not an error here.
+            Err(Error::VersionNotFound { .. }) => return Ok(Vec::new()),
+            other => other?,
+        }
+
+        Ok(result
+            .stdout
+            .split_whitespace()
+            .map(str::to_owned)
+            .collect())
+    }
+
+    fn set_args<'a>(
+        version: AsdfVersion,
+        scope: Scope,
+        name: &'a str,
+        versions: impl Iterator<Item = &'a str>,
+    ) -> (Subcommand, Vec<&'a str>) {
+        let (subcommand, mut run) = if version >= AsdfVersion::GO_REWRITE {
+            match scope {
+                Scope::Current => (Subcommand::Set, vec!["set"]),
+                Scope::Parent => (Subcommand::Set, vec!["set", "--parent"]),
+                Scope::Home => (Subcommand::Set, vec!["set", "--home"]),
+            }
+        } else {
+            match scope {
+                Scope::Current => (Subcommand::Local, vec!["local"]),
+                Scope::Parent => (Subcommand::Local, vec!["local", "--parent"]),
+                Scope::Home => (Subcommand::Global, vec!["global"]),
+            }
+        };
+
+        run.push(name);
+        run.extend(versions);
+
+        (subcommand, run)
+    }
+
+    fn set_output(name: &str, result: CommandResult) -> Result<()> {
+        classify::check(Subject::name(name), &result)
+    }
+
+    /// Install the tools chosen by `spec`, returning the name and [`Installation`] of each of them.
+    /// A tool with several versions set has an [`Installation`] for each version, since `asdf` installs all of them.
+    ///
+    /// A plugin that fails to build is reported as [`InstallOutcome::Failed`], while a plugin that is not
+    /// installed and a version that does not exist are returned as [`Error::PluginNotFound`] and
+    /// [`Error::VersionNotFound`]. With [`InstallSpec::All`], every tool in the nearest `.tool-versions`
+    /// and every other tool with a version set is installed in turn, and an error for one of them is returned
+    /// in its place rather than stopping the others. Otherwise, an error is returned for the whole call.
+    ///
+    /// ```help
+    /// asdf install                            Install all the package versions listed
+    ///                                         in the .tool-versions file
+    /// asdf install <name>                     Install one tool at the version
+    ///                                         specified in the .tool-versions file
+    /// asdf install <name> <version>           Install a specific version of a package
+    /// asdf install <name> latest[:<version>]  Install the latest stable version of a
+    ///                                         package, or with optional version,
+    ///                                         install the latest stable version that
+    ///                                         begins with the given string
+    /// ```
+    pub fn install(spec: InstallSpec) -> Result<Vec<(String, Result<Installation>)>> {
+        default_client().install(spec)
+    }
+
+    /// Remove an installed version of a tool, and report the shims that `asdf` removed or rewrote as a result.
+    ///
+    /// Returns [`Error::PluginNotFound`] if the plugin is not installed, and [`Error::VersionNotInstalled`]
+    /// if the version is not. The shims are found by comparing the `shims` directory in the data directory
+    /// before and after, so changes made by other processes at the same time are included.
+    ///
+    /// ```help
+    /// asdf uninstall <name> <version>         Remove a specific version of a package
+    /// ```
+    pub fn uninstall<A, B>(name: A, version: B) -> Result<Uninstallation>
+    where
+        A: AsRef<str>,
+        B: AsRef<str>,
+    {
+        default_client().uninstall(name, version)
+    }
+
+    /// The version of every tool, or of the tool named, that applies to the working directory,
+    /// where it is set, and whether it is installed.
+    ///
+    /// A tool that is not installed or has no version set is returned with the matching [`VersionStatus`]
+    /// rather than an error, even though `asdf` exits with a failing code.
+    ///
+    /// ```help
+    /// asdf current                            Display current version set or being
+    ///                                         used for all packages
+    /// asdf current <name>                     Display current version set or being
+    ///                                         used for package
+    /// ```
+    pub fn current<A>(name: Option<A>) -> Result<Vec<CurrentVersion>>
+    where
+        A: AsRef<str>,
+    {
+        default_client().current(name)
+    }
+
+    /// The directory that a version of a tool is installed in, or the version that is set if none is given.
+    ///
+    /// Returns [`Error::SystemVersion`] when the version is `system`, [`Error::VersionNotInstalled`]
+    /// when it is not installed, [`Error::NoVersionSet`] when no version is given or set,
+    /// and [`Error::PluginNotFound`] when the plugin is not installed.
+    ///
+    /// ```help
+    /// asdf where <name> [<version>]           Display install path for an installed
+    ///                                         or current version
+    /// ```
+    pub fn locate<A, B>(name: A, version: Option<B>) -> Result<PathBuf>
+    where
+        A: AsRef<str>,
+        B: AsRef<str>,
+    {
+        default_client().locate(name, version)
+    }
+
+    /// The path to the executable that the shim for `command` runs, using the versions set for the working directory.
+    ///
+    /// When no version is set for any tool that provides the command, [`Error::CommandVersionNotSet`]
+    /// lists the plugins and versions that do, and [`Error::CommandNotFound`] is returned when none do.
+    ///
+    /// ```no_run
+    /// use asdf::{packages, Error};
+    ///
+    /// match packages::which("node") {
+    ///     Ok(path) => println!("{}", path.display()),
+    ///     Err(Error::CommandVersionNotSet { versions, .. }) => {
+    ///         for (name, version) in versions {
+    ///             println!("try `asdf set {name} {version}`");
+    ///         }
+    ///     }
+    ///     Err(error) => return Err(error),
+    /// }
+    /// # Ok::<(), asdf::Error>(())
+    /// ```
+    ///
+    /// ```help
+    /// asdf which <command>                    Display the path to an executable
+    /// ```
+    pub fn which<A>(command: A) -> Result<PathBuf>
+    where
+        A: AsRef<str>,
+    {
+        default_client().which(command)
+    }
+
+    /// Write one or more versions of a tool to the `.tool-versions` file chosen by `scope`.
+    /// When more than one version is given, the first is preferred and the rest are fallbacks.
+    ///
+    /// This command was added in `asdf` 0.16.0, replacing `asdf local` and `asdf global`,
+    /// which are used instead for older versions.
+    ///
+    /// ```help
+    /// asdf set [--home|--parent] <name> <version> [<version>...]
+    ///                                         Set a tool version in a .tool-versions
+    ///                                         in the current directory, a parent
+    ///                                         directory or the home directory
+    /// ```
+    pub fn set<A, B, C>(scope: Scope, name: A, versions: C) -> Result<()>
+    where
+        A: AsRef<str>,
+        B: AsRef<str>,
+        C: IntoIterator<Item = B>,
+    {
+        default_client().set(scope, name, versions)
+    }
+
+    /// The same as [`set`] with [`Scope::Current`] and a single version.
+    ///
+    /// ```help
+    /// asdf local <name> <version>             Set the package local version
+    /// asdf local <name> latest[:<version>]    Set the package local version to the
+    ///                                         latest provided version
+    /// ```
+    pub fn local<A, B>(name: A, version: B) -> Result<()>
+    where
+        A: AsRef<str>,
+        B: AsRef<str>,
+    {
+        default_client().local(name, version)
+    }
+
+    /// The same as [`set`] with [`Scope::Home`] and a single version.
+    ///
+    /// ```help
+    /// asdf global <name> <version>            Set the package global version
+    /// asdf global <name> latest[:<version>]   Set the package global version to the
+    ///                                         latest provided version
+    /// ```
+    pub fn global<A, B>(name: A, version: B) -> Result<()>
+    where
+        A: AsRef<str>,
+        B: AsRef<str>,
+    {
+        default_client().global(name, version)
+    }
+
+    /// The environment variable that sets the version of a tool for a shell or process.
+    ///
+    /// A process can not change the environment of the shell that started it, so rather than running
+    /// `asdf shell`, this returns a [`ShellOverride`] to apply to a child process or to print as shell code.
+    /// No command is run, and it works with every version of `asdf`, including those without `asdf shell`.
+    ///
+    /// ```
+    /// use asdf::packages::{self, Shell};
+    ///
+    /// let node = packages::shell("nodejs", "20.1.0");
+    ///
+    /// assert_eq!(node.name(), "ASDF_NODEJS_VERSION");
+    /// assert_eq!(node.to_shell(Shell::Fish), "set -gx ASDF_NODEJS_VERSION '20.1.0'");
+    ///
+    /// let mut command = std::process::Command::new("node");
+    /// node.apply(&mut command);
+    /// ```
+    ///
+    /// ```help
+    /// asdf shell <name> <version>             Set the package version to
+    ///                                         `ASDF_${LANG}_VERSION` in the current shell
+    /// ```
+    pub fn shell<A, B>(name: A, version: B) -> ShellOverride
+    where
+        A: AsRef<str>,
+        B: AsRef<str>,
+    {
+        default_client().shell(name, version)
+    }
+
+    /// The latest stable version of a tool, or the latest that begins with `prefix`.
+    /// Returns [`Error::VersionNotFound`] when no version matches.
+    ///
+    /// ```help
+    /// asdf latest <name> [<version>]          Show latest stable version of a package
+    /// ```
+    pub fn latest<A, B>(name: A, prefix: Option<B>) -> Result<String>
+    where
+        A: AsRef<str>,
+        B: AsRef<str>,
+    {
+        default_client().latest(name, prefix)
+    }
+
+    /// The latest stable version of every installed plugin, and whether it is installed.
+    /// A plugin whose latest version could not be found is [`LatestState::Unknown`] rather than an error.
+    ///
+    /// ```help
+    /// asdf latest --all                       Show latest stable version of all the
+    ///                                         packages and if they are installed
+    /// ```
+    pub fn latest_all() -> Result<Vec<LatestVersion>> {
+        default_client().latest_all()
+    }
+
+    /// The installed versions of the plugin named, or of every plugin, with those that
+    /// begin with `filter` if one is given. A plugin without versions has an empty list.
+    ///
+    /// ```help
+    /// asdf list                               List installed versions of every package
+    /// asdf list <name> [version]              List installed versions of a package and
+    ///                                         optionally filter the versions
+    /// ```
+    pub fn list<A, B>(name: Option<A>, filter: Option<B>) -> Result<InstalledVersions>
+    where
+        A: AsRef<str>,
+        B: AsRef<str>,
+    {
+        default_client().list(name, filter)
+    }
+
+    /// Every version of a tool that the plugin can install, or those that begin with `prefix`,
+    /// in the order that the plugin lists them, which is usually oldest first.
+    ///
+    /// When no version begins with `prefix`, the list is empty. Failing to download the list returns
+    /// [`Error::NoInternet`], and a plugin without a `bin/list-all` script returns [`Error::PluginScriptMissing`].
+    ///
+    /// ```help
+    /// asdf list all <name> [<version>]        List all versions of a package and
+    ///                                         optionally filter the returned versions
+    /// ```
+    pub fn list_all<A, B>(name: A, prefix: Option<B>) -> Result<Vec<String>>
+    where
+        A: AsRef<str>,
+        B: AsRef<str>,
+    {
+        default_client().list_all(name, prefix)
+    }
+}
+
+/// UTILS
+pub mod utils {
+    use super::*;
+    use crate::plugins::{Plugin, PluginSet};
+    use std::{collections::BTreeMap, path::Path};
+
+    /// The debug information printed by [`info`], for attaching to bug reports.
+    ///
+    /// With the `serde` feature enabled, this implements `Serialize` and `Deserialize`.
+    #[derive(Clone, Debug, PartialEq, Getters)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[getset(get = "pub")]
+    pub struct AsdfInfo {
+        /// The output of `uname -a`.
+        os: String,
+        /// The first line of `$SHELL --version`, such as `GNU bash, version 5.2.15(1)-release (x86_64-pc-linux-gnu)`.
+        shell: String,
+        /// The version number taken from [`AsdfInfo::shell`], such as `5.2.15(1)-release`.
+        shell_version: Option<String>,
+        /// The version of Bash that `asdf` runs its scripts with, if it printed one.
+        bash_version: Option<String>,
+        /// The version of `asdf` as it was printed, such as `v0.14.1-f00f759`.
+        asdf_version: String,
+        /// The `ASDF_*` environment variables that `asdf` is using, by name.
+        variables: Environment,
+        /// The installed plugins with their Git URLs and references, as from [`list`](crate::plugins::list).
+        plugins: PluginSet,
+    }
+
+    impl AsdfInfo {
+        /// Parse the sections of `asdf info`, each of which is a heading in capitals followed by its lines:
+        ///
+        /// ```text
+        /// OS:
+        /// Linux host 6.1.0-18-amd64 #1 SMP PREEMPT_DYNAMIC Debian 6.1.76-1 x86_64 GNU/Linux
+        ///
+        /// SHELL:
+        /// zsh 5.9 (x86_64-debian-linux-gnu)
+        ///
+        /// ASDF VERSION:
+        /// v0.14.1-f00f759
+        ///
+        /// ASDF INTERNAL VARIABLES:
+        /// ASDF_DATA_DIR=/home/user/.asdf
+        ///
+        /// ASDF INSTALLED PLUGINS:
+        /// nodejs    https://github.com/asdf-vm/asdf-nodejs.git master 5f1e9b4
+        /// ```
+        fn parse(output: &str) -> Option<Self> {
+            let mut sections = BTreeMap::<&str, Vec<&str>>::new();
+            let mut heading = None;
+
+            for line in output.lines() {
+                match line.strip_suffix(':') {
+                    Some(name)
+                        if !name.is_empty()
+                            && name.chars().all(|c| c.is_ascii_uppercase() || c == ' ') =>
+                    {
+                        heading = Some(name);
+                        sections.entry(name).or_default();
+                    }
+                    _ if line.trim().is_empty() => {}
+                    _ => sections.entry(heading?).or_default().push(line),
+                }
+            }
+
+            let first = |name: &str| {
+                sections
+                    .get(name)?
+                    .first()
+                    .map(|line| line.trim().to_owned())
+            };
+            let shell = first("SHELL")?;
+
+            Some(Self {
+                os: first("OS")?,
+                shell_version: shell
+                    .split([' ', ','])
+                    .find(|word| word.starts_with(|c: char| c.is_ascii_digit()))
+                    .map(str::to_owned),
+                shell,
+                bash_version: first("BASH VERSION"),
+                asdf_version: first("ASDF VERSION")?,
+                // Bash releases title this section `ASDF ENVIRONMENT VARIABLES`, later ones `ASDF INTERNAL VARIABLES`.
+                variables: sections
+                    .iter()
+                    .filter(|(name, _)| name.ends_with("VARIABLES"))
+                    .flat_map(|(_, lines)| lines)
+                    .filter_map(|line| line.trim().split_once('='))
+                    .filter(|(name, _)| name.starts_with("ASDF_"))
+                    .map(|(name, value)| (name.to_owned(), value.to_owned()))
+                    .collect(),
+                plugins: sections
+                    .get("ASDF INSTALLED PLUGINS")
+                    .into_iter()
+                    .flatten()
+                    .filter(|line| !classify::is_empty_message(Subcommand::Info, line))
+                    .map(|line| Plugin::try_from(*line).ok())
+                    .collect::<Option<_>>()?,
+            })
+        }
+    }
+
+    /// What [`reshim`] recreates the shims for.
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    pub enum ReshimTarget {
+        /// Every installed version of every plugin, as `asdf reshim`.
+        All,
+        /// Every installed version of one plugin, as `asdf reshim <name>`.
+        Plugin(String),
+        /// One installed version of a plugin, as `asdf reshim <name> <version>`.
+        Version { name: String, version: String },
+    }
+
+    impl ReshimTarget {
+        fn args(&self) -> Vec<&str> {
+            let mut run = vec!["reshim"];
+            match self {
+                Self::All => {}
+                Self::Plugin(name) => run.push(name),
+                Self::Version { name, version } => run.extend([name.as_str(), version]),
+            }
+            run
+        }
+
+        fn subject(&self) -> Subject<'_> {
+            match self {
+                Self::All => Subject::default(),
+                Self::Plugin(name) => Subject::name(name),
+                Self::Version { name, version } => Subject {
+                    name: Some(name),
+                    version: Some(version),
+                },
+            }
+        }
+    }
+
+    /// The shims that a command created, removed or rewrote, as reported by
+    /// [`uninstall`](crate::packages::uninstall) and [`reshim`]. Each list holds file names in the `shims` directory, sorted.
+    #[derive(Clone, Debug, Default, PartialEq, Eq, Getters)]
+    #[getset(get = "pub")]
+    pub struct ShimChanges {
+        /// Shims that did not exist before.
+        created: Vec<String>,
+        /// Shims that no longer exist.
+        removed: Vec<String>,
+        /// Shims that exist before and after, but were rewritten, usually to change the versions that provide them.
+        updated: Vec<String>,
+    }
+
+    impl ShimChanges {
+        pub(crate) fn between(before: &Shims, after: &Shims) -> Self {
+            let mut changes = Self::default();
+
+            for (name, contents) in &before.0 {
+                match after.0.get(name) {
+                    None => changes.removed.push(name.clone()),
+                    Some(other) if other != contents => changes.updated.push(name.clone()),
+                    Some(_) => {}
+                }
+            }
+            changes.created = after
+                .0
+                .keys()
+                .filter(|name| !before.0.contains_key(*name))
+                .cloned()
+                .collect();
+
+            changes
+        }
+
+        /// Whether nothing changed.
+        pub fn is_empty(&self) -> bool {
+            self.created.is_empty() && self.removed.is_empty() && self.updated.is_empty()
+        }
+    }
+
+    /// The result of [`update`].
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub enum UpdateOutcome {
+        /// `asdf` was updated, from and to the versions printed by `asdf --version`.
+        Updated { from: String, to: String },
+        /// `asdf` was already at the latest version, which is held here.
+        AlreadyLatest(String),
+        /// `asdf` can not update itself, because it was installed with a package manager such as Homebrew,
+        /// or because it is the rewrite in Go. This holds the message `asdf` printed,
+        /// which usually says how to update it instead.
+        Unsupported(String),
+    }
+
+    /// The name and contents of every file in a `shims` directory.
+    #[derive(Clone, Debug, Default)]
+    pub(crate) struct Shims(BTreeMap<String, Vec<u8>>);
+
+    impl Shims {
+        /// Read every shim in `dir`, which is empty if the directory does not exist or can not be read.
+        pub(crate) fn read(dir: &Path) -> Self {
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                return Self::default();
+            };
+
+            Self(
+                entries
+                    .filter_map(|entry| {
+                        let entry = entry.ok()?;
+                        let contents = std::fs::read(entry.path()).ok()?;
+
+                        Some((entry.file_name().to_string_lossy().into_owned(), contents))
+                    })
+                    .collect(),
+            )
+        }
+    }
+
+    impl Asdf {
+        /// `asdf exec`, see [`exec`].
+        pub fn exec<A, B, C>(&self, command: A, args: C) -> Result<RunOutput>
+        where
+            A: AsRef<OsStr>,
+            B: AsRef<OsStr>,
+            C: IntoIterator<Item = B>,
+        {
+            let name = command.as_ref().to_string_lossy();
+
+            self.command_raw(
+                Subcommand::Exec,
+                Subject::name(&name),
+                exec_args(command.as_ref(), args),
+            )
+        }
+
+        /// `asdf exec`, see [`exec`].
+        #[cfg(feature = "tokio")]
+        pub async fn exec_async<A, B, C>(&self, command: A, args: C) -> Result<RunOutput>
+        where
+            A: AsRef<OsStr>,
+            B: AsRef<OsStr>,
+            C: IntoIterator<Item = B>,
+        {
+            let name = command.as_ref().to_string_lossy();
+
+            self.command_raw_async(
+                Subcommand::Exec,
+                Subject::name(&name),
+                exec_args(command.as_ref(), args),
+            )
+            .await
+        }
+
+        /// `asdf exec`, replacing the current process, see [`exec_replace`].
+        #[cfg(unix)]
+        pub fn exec_replace<A, B, C>(&self, command: A, args: C) -> Error
+        where
+            A: AsRef<OsStr>,
+            B: AsRef<OsStr>,
+            C: IntoIterator<Item = B>,
+        {
+            SystemRunner::exec(&self.invocation(exec_args(command.as_ref(), args))).into()
+        }
+
+        /// `asdf env`, see [`env()`].
+        pub fn env<A>(&self, command: A) -> Result<Environment>
+        where
+            A: AsRef<str>,
+        {
+            let command = command.as_ref();
+
+            self.command(Subcommand::Env, ["env", command, "env", "-0"])
+                .and_then(|result| env_output(command, result))
+        }
+
+        /// `asdf env`, see [`env()`].
+        #[cfg(feature = "tokio")]
+        pub async fn env_async<A>(&self, command: A) -> Result<Environment>
+        where
+            A: AsRef<str>,
+        {
+            let command = command.as_ref();
+
+            self.command_async(Subcommand::Env, ["env", command, "env", "-0"])
+                .await
+                .and_then(|result| env_output(command, result))
+        }
+
+        /// `asdf info`, see [`info`].
+        pub fn info(&self) -> Result<AsdfInfo> {
+            self.command(Subcommand::Info, ["info"])
+                .and_then(info_output)
+        }
+
+        /// `asdf info`, see [`info`].
+        #[cfg(feature = "tokio")]
+        pub async fn info_async(&self) -> Result<AsdfInfo> {
+            self.command_async(Subcommand::Info, ["info"])
+                .await
+                .and_then(info_output)
+        }
+
+        /// `asdf reshim`, see [`reshim`].
+        pub fn reshim(&self, target: ReshimTarget) -> Result<ShimChanges> {
+            let shims = self.shims();
+
+            self.command(Subcommand::Reshim, target.args())
+                .and_then(|result| self.reshim_output(&target, shims, result))
+        }
+
+        /// `asdf reshim`, see [`reshim`].
+        #[cfg(feature = "tokio")]
+        pub async fn reshim_async(&self, target: ReshimTarget) -> Result<ShimChanges> {
+            let shims = self.shims();
+
+            self.command_async(Subcommand::Reshim, target.args())
+                .await
+                .and_then(|result| self.reshim_output(&target, shims, result))
+        }
+
+        fn reshim_output(
+            &self,
+            target: &ReshimTarget,
+            before: Shims,
+            result: CommandResult,
+        ) -> Result<ShimChanges> {
+            classify::check(target.subject(), &result)?;
+
+            Ok(ShimChanges::between(&before, &self.shims()))
+        }
+
+        /// `asdf shim-versions`, see [`shim_versions`].
+        pub fn shim_versions<A>(&self, command: A) -> Result<Vec<(String, String)>>
+        where
+            A: AsRef<str>,
+        {
+            let command = command.as_ref();
+
+            self.command(
+                Subcommand::ShimVersions,
+                [shim_versions_arg(self.version()?), command],
+            )
+            .and_then(|result| shim_versions_output(command, result))
+        }
+
+        /// `asdf shim-versions`, see [`shim_versions`].
+        #[cfg(feature = "tokio")]
+        pub async fn shim_versions_async<A>(&self, command: A) -> Result<Vec<(String, String)>>
+        where
+            A: AsRef<str>,
+        {
+            let command = command.as_ref();
+
+            self.command_async(
+                Subcommand::ShimVersions,
+                [shim_versions_arg(self.version_async().await?), command],
+            )
+            .await
+            .and_then(|result| shim_versions_output(command, result))
+        }
+
+        /// `asdf update`, see [`update`].
+        pub fn update(&self, head: bool) -> Result<UpdateOutcome> {
+            if self.version()? >= AsdfVersion::GO_REWRITE {
+                return self
+                    .command(Subcommand::Update, update_args(head))
+                    .map(|result| UpdateOutcome::Unsupported(update_message(&result)));
+            }
+
+            let from = self.printed_version()?;
+            if let Some(outcome) = self
+                .command(Subcommand::Update, update_args(head))
+                .and_then(update_output)?
+            {
+                return Ok(outcome);
+            }
+
+            Ok(updated(from, self.printed_version()?))
+        }
+
+        /// `asdf update`, see [`update`].
+        #[cfg(feature = "tokio")]
+        pub async fn update_async(&self, head: bool) -> Result<UpdateOutcome> {
+            if self.version_async().await? >= AsdfVersion::GO_REWRITE {
+                return self
+                    .command_async(Subcommand::Update, update_args(head))
+                    .await
+                    .map(|result| UpdateOutcome::Unsupported(update_message(&result)));
+            }
+
+            let from = self.printed_version_async().await?;
+            if let Some(outcome) = self
+                .command_async(Subcommand::Update, update_args(head))
+                .await
+                .and_then(update_output)?
+            {
+                return Ok(outcome);
+            }
+
+            Ok(updated(from, self.printed_version_async().await?))
+        }
+
+        /// The output of `asdf --version`, which is run again each time rather than remembered like [`Asdf::version`].
+        fn printed_version(&self) -> Result<String> {
+            self.command(Subcommand::Version, ["--version"])
+                .and_then(printed_version_output)
+        }
+
+        /// See [`Asdf::printed_version`].
+        #[cfg(feature = "tokio")]
+        async fn printed_version_async(&self) -> Result<String> {
+            self.command_async(Subcommand::Version, ["--version"])
+                .await
+                .and_then(printed_version_output)
+        }
     }
 
-    /// ```help
-    /// asdf shell <name> <version>             Set the package version to
-    ///                                         `ASDF_${LANG}_VERSION` in the current shell
-    /// ```
-    pub fn shell<A, B>(name: A, version: B) -> !
-    where
-        A: AsRef<str>,
-        B: AsRef<str>,
-    {
-        todo!();
+    /// Environment variables by name, as returned from [`env()`].
+    pub type Environment = BTreeMap<String, String>;
+
+    /// Parse the output of `env -0`, where each `NAME=value` ends with a NUL byte rather than a newline,
+    /// so that values containing newlines are kept whole.
+    fn env_output(command: &str, result: CommandResult) -> Result<Environment> {
+        classify::check(Subject::name(command), &result)?;
+
+        result
+            .stdout
+            .split_terminator('\0')
+            .map(|variable| {
+                variable
+                    .split_once('=')
+                    .map(|(name, value)| (name.to_owned(), value.to_owned()))
+            })
+            .collect::<Option<_>>()
+            .ok_or_else(|| Error::MalformedOutput(result.failure()))
     }
 
-    /// ```help
-    /// asdf latest <name> [<version>]          Show latest stable version of a package
-    /// ```
-    pub fn latest<A, B>(name: A, version: Option<B>) -> !
-    where
-        A: AsRef<str>,
-        B: AsRef<str>,
-    {
-        todo!();
+    fn info_output(result: CommandResult) -> Result<AsdfInfo> {
+        classify::check(Subject::default(), &result)?;
+
+        AsdfInfo::parse(&result.stdout).ok_or_else(|| Error::MalformedOutput(result.failure()))
     }
 
-    /// ```help
-    /// asdf latest --all                       Show latest stable version of all the
-    ///                                         packages and if they are installed
-    /// ```
-    pub fn latest_all() -> ! {
-        todo!();
+    /// The command was renamed from `shim-versions` to `shimversions` in the rewrite.
+    fn shim_versions_arg(version: AsdfVersion) -> &'static str {
+        if version >= AsdfVersion::GO_REWRITE {
+            "shimversions"
+        } else {
+            "shim-versions"
+        }
     }
 
-    /// ```help
-    /// asdf list <name> [version]              List installed versions of a package and
-    ///                                         optionally filter the versions
+    /// Parse the output of `asdf shim-versions`, which is a plugin and version on each line:
+    ///
+    /// ```text
+    /// nodejs 18.0.0
+    /// nodejs 20.1.0
     /// ```
-    pub fn list<A, B>(name: A, version: Option<B>) -> !
+    fn shim_versions_output(command: &str, result: CommandResult) -> Result<Vec<(String, String)>> {
+        classify::check(Subject::name(command), &result)?;
+
+        result
+            .stdout
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(
+                |line| match line.split_whitespace().collect::<Vec<_>>()[..] {
+                    [plugin, version] => Some((plugin.to_owned(), version.to_owned())),
+                    _ => None,
+                },
+            )
+            .collect::<Option<_>>()
+            .ok_or_else(|| Error::MalformedOutput(result.failure()))
+    }
+
+    fn update_args(head: bool) -> Vec<&'static str> {
+        let mut run = vec!["update"];
+        if head {
+            run.push("--head");
+        }
+        run
+    }
+
+    fn update_message(result: &CommandResult) -> String {
+        [result.stdout.trim(), result.stderr.trim()]
+            .into_iter()
+            .filter(|output| !output.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Return [`UpdateOutcome::Unsupported`] when `asdf` refuses to update itself, or [`None`] when the update ran.
+    fn update_output(result: CommandResult) -> Result<Option<UpdateOutcome>> {
+        match classify::check(Subject::default(), &result) {
+            Err(Error::UpdateDisabled(_)) => {
+                Ok(Some(UpdateOutcome::Unsupported(update_message(&result))))
+            }
+            other => other.map(|_| None),
+        }
+    }
+
+    fn printed_version_output(result: CommandResult) -> Result<String> {
+        classify::check(Subject::default(), &result)?;
+
+        match result.stdout.trim() {
+            "" => Err(Error::MalformedOutput(result.failure())),
+            version => Ok(version.to_owned()),
+        }
+    }
+
+    fn updated(from: String, to: String) -> UpdateOutcome {
+        if from == to {
+            UpdateOutcome::AlreadyLatest(to)
+        } else {
+            UpdateOutcome::Updated { from, to }
+        }
+    }
+
+    fn exec_args<B, C>(command: &OsStr, args: C) -> Vec<OsString>
     where
-        A: AsRef<str>,
-        B: AsRef<str>,
+        B: AsRef<OsStr>,
+        C: IntoIterator<Item = B>,
     {
-        todo!();
+        [OsStr::new("exec"), command]
+            .into_iter()
+            .map(OsStr::to_owned)
+            .chain(args.into_iter().map(|arg| arg.as_ref().to_owned()))
+            .collect()
     }
 
+    /// Run `command` with the versions set for the working directory, as its shim would, and return its exit code
+    /// and output. Standard input is not connected. A command that runs and fails is not an error,
+    /// unless `asdf` reports that no version is set for it ([`Error::CommandVersionNotSet`])
+    /// or that no version provides it ([`Error::CommandNotFound`]).
+    ///
+    /// To hand the terminal over to the command instead, see [`exec_replace`].
+    ///
     /// ```help
-    /// asdf list all <name> [<version>]        List all versions of a package and
-    ///                                         optionally filter the returned versions
+    /// asdf exec <command> [args...]           Executes the command shim for current version
     /// ```
-    pub fn list_all<A, B>(name: A, version: Option<B>) -> !
+    pub fn exec<A, B, C>(command: A, args: C) -> Result<RunOutput>
     where
-        A: AsRef<str>,
-        B: AsRef<str>,
+        A: AsRef<OsStr>,
+        B: AsRef<OsStr>,
+        C: IntoIterator<Item = B>,
     {
-        todo!();
+        default_client().exec(command, args)
     }
-}
-
-/// UTILS
-pub mod utils {
-    use super::*;
 
-    /// ```help
-    /// asdf exec <command> [args...]           Executes the command shim for current version
+    /// Replace the current process with `asdf exec <command> [args...]`, so that a wrapper
+    /// can hand off to a tool as if it was run directly. The arguments are passed unchanged,
+    /// and the process keeps its ID, standard input and output, and signal handling.
+    ///
+    /// This only returns if the process could not be replaced, with [`Error::AsdfNotFound`]
+    /// or [`Error::IoError`]. The runner, timeout and cancel handle of the client are not used.
+    ///
+    /// ```no_run
+    /// let error = asdf::utils::exec_replace("node", std::env::args_os().skip(1));
+    ///
+    /// eprintln!("{error}");
+    /// std::process::exit(127);
     /// ```
-    pub fn exec<A, B, C>(command: A, args: C) -> !
+    #[cfg(unix)]
+    pub fn exec_replace<A, B, C>(command: A, args: C) -> Error
     where
-        A: AsRef<str>,
-        B: AsRef<str>,
+        A: AsRef<OsStr>,
+        B: AsRef<OsStr>,
         C: IntoIterator<Item = B>,
     {
-        todo!();
+        default_client().exec_replace(command, args)
     }
 
+    /// The environment that the shim for `command` runs it with, which includes the `PATH` of the versions
+    /// set for the working directory. This runs `env -0` as the util, so that values with newlines are parsed safely.
+    ///
+    /// ```no_run
+    /// let environment = asdf::utils::env("node")?;
+    ///
+    /// std::process::Command::new("node")
+    ///     .env_clear()
+    ///     .envs(&environment)
+    ///     .status()?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    ///
     /// ```help
     /// asdf env <command> [util]               Runs util (default: `env`) inside the
     ///                                         environment used for command shim execution.
     /// ```
-    pub fn env<A, B>(command: A, util: B) -> !
+    pub fn env<A>(command: A) -> Result<Environment>
     where
         A: AsRef<str>,
-        B: AsRef<str>,
     {
-        todo!();
+        default_client().env(command)
     }
 
+    /// The operating system, shell, version and configuration of `asdf`, and the installed plugins.
+    /// With the `serde` feature enabled, the [`AsdfInfo`] can be serialized to attach to a bug report.
+    ///
     /// ```help
     /// asdf info                               Print OS, Shell and ASDF debug information.
     /// ```
-    pub fn info() -> ! {
-        todo!();
+    pub fn info() -> Result<AsdfInfo> {
+        default_client().info()
     }
 
+    /// Recreate the shims for every installed version, for every version of one plugin,
+    /// or for one version of it, returning the shims that were created or removed.
+    ///
+    /// This is needed after installing a package that provides new executables, such as `npm install -g`.
+    ///
     /// ```help
     /// asdf reshim <name> <version>            Recreate shims for version of a package
     /// ```
-    pub fn reshim<A, B>(name: A, version: B) -> !
-    where
-        A: AsRef<str>,
-        B: AsRef<str>,
-    {
-        todo!();
+    pub fn reshim(target: ReshimTarget) -> Result<ShimChanges> {
+        default_client().reshim(target)
     }
 
+    /// The plugin and version of every installed tool that provides `command`, whether or not it is set.
+    /// A command without a shim returns [`Error::CommandNotFound`].
+    ///
+    /// Since `0.16.0` this runs `asdf shimversions`, which is what the command was renamed to.
+    ///
     /// ```help
     /// asdf shim-versions <command>            List the plugins and versions that
     ///                                         provide a command
     /// ```
-    pub fn shim_versions<A>(name: A) -> !
+    pub fn shim_versions<A>(command: A) -> Result<Vec<(String, String)>>
     where
         A: AsRef<str>,
     {
-        todo!();
+        default_client().shim_versions(command)
     }
 
+    /// Update `asdf` to the latest release, or to the latest commit on the default branch if `head` is `true`,
+    /// comparing the output of `asdf --version` before and after.
+    ///
+    /// Only a Git clone of `asdf` can update itself. When it was installed with a package manager,
+    /// or since `0.16.0` where this command does nothing, [`UpdateOutcome::Unsupported`] is returned rather
+    /// than an error. The version remembered by the client (see [`Asdf::version`]) is not changed by an update.
+    ///
     /// ```help
     /// asdf update                             Update asdf to the latest stable release
     /// asdf update --head                      Update asdf to the latest on the master branch
     /// ```
-    pub fn update(head: bool) -> ! {
-        todo!();
+    pub fn update(head: bool) -> Result<UpdateOutcome> {
+        default_client().update(head)
     }
 
     /// Originally in the 'MANAGE PACKAGES' section, moved because that seems wrong...
@@ -487,3 +3234,999 @@ pub mod utils {
         todo!();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::ReplayRunner;
+    use packages::{
+        InstallOutcome, InstallSpec, LatestState, Scope, Shell, ShellOverride, VersionSource,
+        VersionStatus,
+    };
+    use plugins::PluginSet;
+    use utils::{ReshimTarget, ShimChanges, Shims, UpdateOutcome};
+
+    fn client(runner: ReplayRunner) -> Asdf {
+        Asdf::new()
+            .with_version(AsdfVersion::new(0, 14, 1))
+            .with_runner(runner)
+    }
+
+    #[test]
+    fn version_is_detected_once() {
+        let runner = ReplayRunner::new()
+            .expect(
+                ["--version"],
+                RunOutput::exited(0).with_stdout("asdf version 0.16.7\n"),
+            )
+            .expect(["plugin", "list", "--urls", "--refs"], RunOutput::exited(0))
+            .expect(["plugin", "list", "--urls", "--refs"], RunOutput::exited(0));
+        let asdf = Asdf::new().with_runner(runner.clone());
+
+        asdf.plugin_list().unwrap();
+        asdf.clone().plugin_list().unwrap();
+
+        assert_eq!(asdf.version().unwrap(), AsdfVersion::new(0, 16, 7));
+        assert!(runner.is_finished());
+    }
+
+    #[test]
+    fn version_is_detected_again_with_env() {
+        let runner = ReplayRunner::new()
+            .expect(
+                ["--version"],
+                RunOutput::exited(0).with_stdout("v0.14.1-f00f759\n"),
+            )
+            .expect(
+                ["--version"],
+                RunOutput::exited(0).with_stdout("asdf version 0.16.7\n"),
+            );
+        let asdf = Asdf::new().with_runner(runner.clone());
+
+        assert_eq!(asdf.version().unwrap(), AsdfVersion::new(0, 14, 1));
+        let asdf = asdf.with_env("ASDF_DIR", "/opt/asdf");
+        assert_eq!(asdf.version().unwrap(), AsdfVersion::new(0, 16, 7));
+        assert!(runner.is_finished());
+    }
+
+    #[test]
+    fn plugin_list_parses_urls_and_refs() {
+        let asdf = client(ReplayRunner::new().expect(
+            ["plugin", "list", "--urls", "--refs"],
+            RunOutput::exited(0).with_stdout(
+                "nodejs   https://github.com/asdf-vm/asdf-nodejs.git   master   5f1e9b4\n\
+                 rust     https://github.com/code-lever/asdf-rust.git  main     0d3c1f8\n",
+            ),
+        ));
+
+        let plugins = asdf.plugin_list().unwrap();
+
+        assert_eq!(plugins.len(), 2);
+        assert_eq!(plugins[1].name(), "rust");
+        assert_eq!(
+            plugins[1].git_url(),
+            "https://github.com/code-lever/asdf-rust.git"
+        );
+        assert_eq!(plugins[1].git_branch().as_deref(), Some("main"));
+        assert_eq!(plugins[1].git_ref().as_deref(), Some("0d3c1f8"));
+    }
+
+    #[test]
+    fn plugin_list_empty() {
+        let asdf = client(ReplayRunner::new().expect(
+            ["plugin", "list", "--urls", "--refs"],
+            RunOutput::exited(0).with_stderr("No plugins installed\n"),
+        ));
+
+        assert_eq!(asdf.plugin_list().unwrap(), PluginSet::new());
+    }
+
+    #[test]
+    fn plugin_add_already_added() {
+        let asdf = client(ReplayRunner::new().expect(
+            ["plugin", "add", "nodejs"],
+            RunOutput::exited(2).with_stderr("Plugin named nodejs already added\n"),
+        ));
+
+        let error = asdf.plugin_add("nodejs", None::<&str>).unwrap_err();
+
+        assert!(matches!(&error, Error::PluginAlreadyAdded { name, .. } if name == "nodejs"));
+        assert_eq!(error.failure().unwrap().code(), &Some(2));
+        assert_eq!(
+            error.to_string(),
+            "the plugin `nodejs` was already added: \
+             `asdf plugin add nodejs` exited with code 2: Plugin named nodejs already added"
+        );
+    }
+
+    #[test]
+    fn interrupted_command_keeps_partial_output() {
+        let asdf = client(ReplayRunner::new().expect(
+            ["plugin", "add", "nodejs"],
+            RunOutput::killed(Interruption::TimedOut).with_stdout("Cloning into 'nodejs'...\n"),
+        ));
+
+        match asdf.plugin_add("nodejs", None::<&str>) {
+            Err(Error::TimedOut(failure)) => {
+                assert_eq!(failure.stdout(), "Cloning into 'nodejs'...\n")
+            }
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+
+    #[test]
+    fn install_reports_each_tool() {
+        let dir = std::env::temp_dir().join(format!("asdf-install-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("project")).unwrap();
+        std::fs::write(
+            dir.join(".tool-versions"),
+            "nodejs 20.1.0\n# pinned for the build\npython 3.12.0\nrust 1.78.0\ndeno 1.44.0\n",
+        )
+        .unwrap();
+
+        let runner = ReplayRunner::new()
+            .expect(
+                ["current"],
+                RunOutput::exited(0).with_stdout(format!(
+                    "nodejs          20.1.0          {0}/.tool-versions\n\
+                     ruby            3.3.0           ASDF_RUBY_VERSION environment variable\n\
+                     rust            1.78.0          {0}/.tool-versions\n\
+                     zig             ______          No version is set. Run \"asdf <global|shell|local> zig <version>\"\n",
+                    dir.display()
+                )),
+            )
+            .expect(
+                ["install", "nodejs"],
+                RunOutput::exited(0).with_stdout("nodejs 20.1.0 is already installed\n"),
+            )
+            .expect(
+                ["install", "python"],
+                RunOutput::exited(1).with_stderr("No such plugin: python\n"),
+            )
+            .expect(
+                ["install", "rust"],
+                RunOutput::exited(1).with_stderr("error: linker `cc` not found\n"),
+            )
+            .expect(
+                ["install", "deno"],
+                RunOutput::exited(1)
+                    .with_stderr("curl: (6) Could not resolve host: github.com\n"),
+            )
+            .expect(["install", "ruby"], RunOutput::exited(0));
+        let asdf = client(runner.clone()).with_current_dir(dir.join("project"));
+
+        let installations = asdf.install(InstallSpec::All);
+        std::fs::remove_dir_all(&dir).unwrap();
+        let installations = installations.unwrap();
+
+        let names = installations
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["nodejs", "python", "rust", "deno", "ruby"]);
+        let installation = installations[0].1.as_ref().unwrap();
+        assert_eq!(installation.version().as_deref(), Some("20.1.0"));
+        assert_eq!(installation.outcome(), &InstallOutcome::AlreadyInstalled);
+        assert!(matches!(
+            &installations[1].1,
+            Err(Error::PluginNotFound { name, .. }) if name == "python"
+        ));
+        assert!(matches!(
+            installations[2].1.as_ref().unwrap().outcome(),
+            InstallOutcome::Failed(failure) if failure.summary() == Some("error: linker `cc` not found")
+        ));
+        assert!(matches!(&installations[3].1, Err(Error::NoInternet(_))));
+        let installation = installations[4].1.as_ref().unwrap();
+        assert_eq!(installation.version().as_deref(), Some("3.3.0"));
+        assert_eq!(installation.outcome(), &InstallOutcome::Installed);
+        assert!(runner.is_finished());
+    }
+
+    #[test]
+    fn install_reports_each_version_of_a_tool() {
+        let runner = ReplayRunner::new()
+            .expect(
+                ["current", "python"],
+                RunOutput::exited(0).with_stdout(
+                    "python          3.12.0 3.11.9   /home/user/project/.tool-versions\n",
+                ),
+            )
+            .expect(
+                ["install", "python"],
+                RunOutput::exited(0).with_stdout("python 3.11.9 is already installed\n"),
+            );
+        let asdf = client(runner.clone());
+
+        let installations = asdf
+            .install(InstallSpec::Tool("python".to_owned()))
+            .unwrap()
+            .into_iter()
+            .map(|(_, installation)| installation.unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(installations.len(), 2);
+        assert_eq!(installations[0].version().as_deref(), Some("3.12.0"));
+        assert_eq!(installations[0].outcome(), &InstallOutcome::Installed);
+        assert_eq!(installations[1].version().as_deref(), Some("3.11.9"));
+        assert_eq!(
+            installations[1].outcome(),
+            &InstallOutcome::AlreadyInstalled
+        );
+        assert!(runner.is_finished());
+    }
+
+    #[test]
+    fn install_latest_with_prefix() {
+        let asdf = client(
+            ReplayRunner::new()
+                .expect(["install", "nodejs", "latest:20"], RunOutput::exited(0))
+                .expect(
+                    ["install", "nodejs", "latest:99"],
+                    RunOutput::exited(1)
+                        .with_stderr("No compatible versions available (nodejs 99)\n"),
+                ),
+        );
+        let latest = |prefix: &str| InstallSpec::Latest {
+            name: "nodejs".to_owned(),
+            prefix: Some(prefix.to_owned()),
+        };
+
+        let installations = asdf.install(latest("20")).unwrap();
+        let installation = installations[0].1.as_ref().unwrap();
+
+        assert_eq!(installation.outcome(), &InstallOutcome::Installed);
+        assert_eq!(installation.version(), &None);
+        assert!(matches!(
+            asdf.install(latest("99")),
+            Err(Error::VersionNotFound { version, .. }) if version == "latest:99"
+        ));
+    }
+
+    #[test]
+    fn install_already_installed_since_go_rewrite() {
+        let asdf = client(ReplayRunner::new().expect(
+            ["install", "nodejs", "20.1.0"],
+            RunOutput::exited(0).with_stderr("version 20.1.0 of nodejs is already installed\n"),
+        ))
+        .with_version(AsdfVersion::new(0, 16, 7));
+
+        let installations = asdf
+            .install(InstallSpec::Version {
+                name: "nodejs".to_owned(),
+                version: "20.1.0".to_owned(),
+            })
+            .unwrap();
+
+        assert_eq!(
+            installations[0].1.as_ref().unwrap().outcome(),
+            &InstallOutcome::AlreadyInstalled
+        );
+    }
+
+    /// Removes a version from the data directory before replying, as `asdf uninstall` would.
+    #[derive(Debug)]
+    struct UninstallRunner {
+        runner: ReplayRunner,
+        data_dir: PathBuf,
+    }
+
+    impl Runner for UninstallRunner {
+        fn run(&self, invocation: &Invocation) -> std::io::Result<RunOutput> {
+            let shims = self.data_dir.join("shims");
+
+            std::fs::remove_dir_all(self.data_dir.join("installs/nodejs/20.1.0"))?;
+            std::fs::remove_file(shims.join("npx"))?;
+            std::fs::write(shims.join("node"), "# asdf-plugin: nodejs 18.0.0\n")?;
+
+            self.runner.run(invocation)
+        }
+    }
+
+    #[test]
+    fn uninstall_reports_removed_version_and_shims() {
+        let data_dir = std::env::temp_dir().join(format!("asdf-uninstall-{}", std::process::id()));
+        let shims = data_dir.join("shims");
+        std::fs::create_dir_all(data_dir.join("installs/nodejs/20.1.0/bin")).unwrap();
+        std::fs::create_dir_all(&shims).unwrap();
+        std::fs::write(
+            shims.join("node"),
+            "# asdf-plugin: nodejs 18.0.0\n# asdf-plugin: nodejs 20.1.0\n",
+        )
+        .unwrap();
+        std::fs::write(shims.join("npx"), "# asdf-plugin: nodejs 20.1.0\n").unwrap();
+
+        let runner =
+            ReplayRunner::new().expect(["uninstall", "nodejs", "20.1.0"], RunOutput::exited(0));
+        let asdf = Asdf::new()
+            .with_version(AsdfVersion::new(0, 14, 1))
+            .with_data_dir(&data_dir)
+            .with_runner(UninstallRunner {
+                runner: runner.clone(),
+                data_dir: data_dir.clone(),
+            });
+
+        let uninstallation = asdf.uninstall("nodejs", "20.1.0");
+        std::fs::remove_dir_all(&data_dir).unwrap();
+        let uninstallation = uninstallation.unwrap();
+
+        assert!(uninstallation.removed());
+        assert!(uninstallation.shims().created().is_empty());
+        assert_eq!(uninstallation.shims().removed(), &["npx"]);
+        assert_eq!(uninstallation.shims().updated(), &["node"]);
+        assert!(runner.is_finished());
+    }
+
+    #[test]
+    fn uninstall_errors() {
+        let asdf = client(
+            ReplayRunner::new()
+                .expect(
+                    ["uninstall", "nodejz", "20.1.0"],
+                    RunOutput::exited(1).with_stderr("No such plugin: nodejz\n"),
+                )
+                .expect(
+                    ["uninstall", "nodejs", "99"],
+                    RunOutput::exited(1).with_stderr("No such version\n"),
+                ),
+        );
+
+        assert!(matches!(
+            asdf.uninstall("nodejz", "20.1.0"),
+            Err(Error::PluginNotFound { name, .. }) if name == "nodejz"
+        ));
+        assert!(matches!(
+            asdf.uninstall("nodejs", "99"),
+            Err(Error::VersionNotInstalled { name, version: Some(version), .. })
+                if name == "nodejs" && version == "99"
+        ));
+    }
+
+    #[test]
+    fn shim_changes_between_directories() {
+        let dir = std::env::temp_dir().join(format!("asdf-shims-{}", std::process::id()));
+        let (before, after) = (dir.join("before"), dir.join("after"));
+        std::fs::create_dir_all(&before).unwrap();
+        std::fs::create_dir_all(&after).unwrap();
+        for (path, contents) in [
+            (
+                before.join("node"),
+                "# asdf-plugin: nodejs 18.0.0\n# asdf-plugin: nodejs 20.1.0\n",
+            ),
+            (before.join("npx"), "# asdf-plugin: nodejs 20.1.0\n"),
+            (before.join("cargo"), "# asdf-plugin: rust 1.80.0\n"),
+            (after.join("node"), "# asdf-plugin: nodejs 18.0.0\n"),
+            (after.join("cargo"), "# asdf-plugin: rust 1.80.0\n"),
+            (after.join("rustc"), "# asdf-plugin: rust 1.80.0\n"),
+        ] {
+            std::fs::write(path, contents).unwrap();
+        }
+
+        let changes = ShimChanges::between(&Shims::read(&before), &Shims::read(&after));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(changes.created(), &["rustc"]);
+        assert_eq!(changes.removed(), &["npx"]);
+        assert_eq!(changes.updated(), &["node"]);
+        assert!(ShimChanges::between(&Shims::read(&dir), &Shims::default()).is_empty());
+    }
+
+    #[test]
+    fn current_before_go_rewrite() {
+        let asdf = client(ReplayRunner::new().expect(
+            ["current"],
+            RunOutput::exited(126).with_stdout(
+                "nodejs          18.0.0 20.1.0   /home/user/my project/.tool-versions\n\
+                 python          3.12.0          Not installed. Run \"asdf install python 3.12.0\"\n\
+                 ruby            3.3.0           ASDF_RUBY_VERSION environment variable\n\
+                 rust            ______          No version is set. Run \"asdf <global|shell|local> rust <version>\"\n",
+            ),
+        ));
+
+        let current = asdf.current(None::<&str>).unwrap();
+
+        assert_eq!(current.len(), 4);
+        assert_eq!(current[0].versions(), &["18.0.0", "20.1.0"]);
+        assert_eq!(
+            current[0].source(),
+            &Some(VersionSource::File(
+                "/home/user/my project/.tool-versions".into()
+            ))
+        );
+        assert_eq!(current[1].status(), &VersionStatus::NotInstalled);
+        assert_eq!(
+            current[2].source(),
+            &Some(VersionSource::EnvVar("ASDF_RUBY_VERSION".to_owned()))
+        );
+        assert_eq!(current[3].status(), &VersionStatus::NoVersionSet);
+        assert!(current[3].versions().is_empty());
+    }
+
+    #[test]
+    fn current_since_go_rewrite() {
+        let asdf = client(ReplayRunner::new().expect(
+            ["current", "nodejs"],
+            RunOutput::exited(0).with_stdout(
+                "Name            Version         Source                          Installed\n\
+                 nodejs          20.1.0          /home/user/my project/.tool-versions true\n",
+            ),
+        ))
+        .with_version(AsdfVersion::new(0, 16, 7));
+
+        let current = asdf.current(Some("nodejs")).unwrap();
+
+        assert_eq!(current.len(), 1);
+        assert_eq!(current[0].name(), "nodejs");
+        assert_eq!(current[0].versions(), &["20.1.0"]);
+        assert_eq!(
+            current[0].source(),
+            &Some(VersionSource::File(
+                "/home/user/my project/.tool-versions".into()
+            ))
+        );
+        assert_eq!(current[0].status(), &VersionStatus::Installed);
+    }
+
+    #[test]
+    fn locate_install_path_and_errors() {
+        let asdf = client(
+            ReplayRunner::new()
+                .expect(
+                    ["where", "nodejs"],
+                    RunOutput::exited(0).with_stdout("/home/user/.asdf/installs/nodejs/20.1.0\n"),
+                )
+                .expect(
+                    ["where", "python"],
+                    RunOutput::exited(1).with_stderr("System version is selected\n"),
+                )
+                .expect(
+                    ["where", "nodejs", "99"],
+                    RunOutput::exited(1).with_stderr("Version not installed\n"),
+                )
+                .expect(
+                    ["where", "nodejz"],
+                    RunOutput::exited(1).with_stderr("No such plugin: nodejz\n"),
+                )
+                .expect(
+                    ["where", "rust"],
+                    RunOutput::exited(1).with_stderr(
+                        "No version is set for rust; please run `asdf <global | shell | local> rust <version>`\n",
+                    ),
+                ),
+        );
+
+        assert_eq!(
+            asdf.locate("nodejs", None::<&str>).unwrap(),
+            PathBuf::from("/home/user/.asdf/installs/nodejs/20.1.0")
+        );
+        assert!(matches!(
+            asdf.locate("python", None::<&str>),
+            Err(Error::SystemVersion { name, .. }) if name == "python"
+        ));
+
+        let error = asdf.locate("nodejs", Some("99")).unwrap_err();
+
+        assert!(matches!(error, Error::VersionNotInstalled { .. }));
+        assert!(error
+            .to_string()
+            .starts_with("version `99` of `nodejs` is not installed"));
+        assert!(matches!(
+            asdf.locate("nodejz", None::<&str>),
+            Err(Error::PluginNotFound { name, .. }) if name == "nodejz"
+        ));
+        assert!(matches!(
+            asdf.locate("rust", None::<&str>),
+            Err(Error::NoVersionSet { name, .. }) if name == "rust"
+        ));
+    }
+
+    #[test]
+    fn which_lists_versions_when_none_is_set() {
+        let asdf = client(
+            ReplayRunner::new()
+                .expect(
+                    ["which", "node"],
+                    RunOutput::exited(0)
+                        .with_stdout("/home/user/.asdf/installs/nodejs/20.1.0/bin/node\n"),
+                )
+                .expect(
+                    ["which", "npx"],
+                    RunOutput::exited(126).with_stderr(
+                        "No version is set for command npx\n\
+                         Consider adding one of the following versions in your config file at /home/user/project/.tool-versions\n\
+                         nodejs 18.0.0\n\
+                         nodejs 20.1.0\n",
+                    ),
+                ),
+        );
+
+        assert_eq!(
+            asdf.which("node").unwrap(),
+            PathBuf::from("/home/user/.asdf/installs/nodejs/20.1.0/bin/node")
+        );
+        match asdf.which("npx") {
+            Err(Error::CommandVersionNotSet {
+                command, versions, ..
+            }) => {
+                assert_eq!(command, "npx");
+                assert_eq!(
+                    versions,
+                    [
+                        ("nodejs".to_owned(), "18.0.0".to_owned()),
+                        ("nodejs".to_owned(), "20.1.0".to_owned())
+                    ]
+                );
+            }
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+
+    #[test]
+    fn shell_override_names_and_quotes() {
+        let lts = ShellOverride::new("nodejs-lts", Some("it's 20"));
+        let unset = ShellOverride::new("rust", None::<&str>);
+
+        assert_eq!(lts.name(), "ASDF_NODEJS_LTS_VERSION");
+        assert_eq!(
+            lts.to_shell(Shell::Bash),
+            r"export ASDF_NODEJS_LTS_VERSION='it'\''s 20'"
+        );
+        assert_eq!(
+            lts.to_shell(Shell::Fish),
+            r"set -gx ASDF_NODEJS_LTS_VERSION 'it\'s 20'"
+        );
+        assert_eq!(unset.to_shell(Shell::Zsh), "unset ASDF_RUST_VERSION");
+        assert_eq!(unset.to_shell(Shell::Fish), "set -e ASDF_RUST_VERSION");
+    }
+
+    #[test]
+    fn latest_with_prefix() {
+        let asdf = client(
+            ReplayRunner::new()
+                .expect(
+                    ["latest", "nodejs", "18"],
+                    RunOutput::exited(0).with_stdout("18.20.4\n"),
+                )
+                .expect(
+                    ["latest", "nodejs", "99"],
+                    RunOutput::exited(1)
+                        .with_stderr("No compatible versions available (nodejs 99)\n"),
+                ),
+        );
+
+        assert_eq!(asdf.latest("nodejs", Some("18")).unwrap(), "18.20.4");
+        assert!(matches!(
+            asdf.latest("nodejs", Some("99")),
+            Err(Error::VersionNotFound { version, .. }) if version == "99"
+        ));
+    }
+
+    #[test]
+    fn latest_all_states() {
+        let asdf = client(ReplayRunner::new().expect(
+            ["latest", "--all"],
+            RunOutput::exited(0).with_stdout(
+                "nodejs\t22.9.0\tinstalled\n\
+                 python\t3.13.0\tmissing\n\
+                 rust\tunknown\tmissing\n",
+            ),
+        ));
+
+        let latest = asdf.latest_all().unwrap();
+
+        assert_eq!(latest.len(), 3);
+        assert_eq!(
+            latest[0].state(),
+            &LatestState::Installed("22.9.0".to_owned())
+        );
+        assert_eq!(latest[1].version(), Some("3.13.0"));
+        assert_eq!(latest[2].state(), &LatestState::Unknown);
+    }
+
+    #[test]
+    fn list_every_plugin() {
+        let asdf = client(
+            ReplayRunner::new().expect(
+                ["list"],
+                RunOutput::exited(0)
+                    .with_stdout("nodejs\n  18.0.0\n *20.1.0\n  20.2.0\nrust\n")
+                    .with_stderr("  No versions installed\n"),
+            ),
+        );
+
+        let versions = asdf.list(None::<&str>, Some("20")).unwrap();
+
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions["nodejs"].len(), 2);
+        assert_eq!(versions["nodejs"][0].version(), "20.1.0");
+        assert!(versions["nodejs"][0].current());
+        assert!(!versions["nodejs"][1].current());
+        assert!(versions["rust"].is_empty());
+    }
+
+    #[test]
+    fn list_without_plugins() {
+        let asdf = client(ReplayRunner::new().expect(
+            ["list"],
+            RunOutput::exited(0).with_stdout("No plugins installed\n"),
+        ));
+
+        assert!(asdf.list(None::<&str>, None::<&str>).unwrap().is_empty());
+    }
+
+    #[test]
+    fn list_one_plugin_without_matches() {
+        let asdf = client(ReplayRunner::new().expect(
+            ["list", "nodejs", "99"],
+            RunOutput::exited(1).with_stderr("No compatible versions installed (nodejs 99)\n"),
+        ));
+
+        let versions = asdf.list(Some("nodejs"), Some("99")).unwrap();
+
+        assert_eq!(versions.len(), 1);
+        assert!(versions["nodejs"].is_empty());
+    }
+
+    #[test]
+    fn list_all_keeps_order_and_errors() {
+        let asdf = client(
+            ReplayRunner::new()
+                .expect(
+                    ["list", "all", "nodejs", "20.1"],
+                    RunOutput::exited(0).with_stdout("20.1.0\n20.10.0\n20.2.0\n"),
+                )
+                .expect(
+                    ["list", "all", "nodejs", "99"],
+                    RunOutput::exited(1)
+                        .with_stderr("No compatible versions available (nodejs 99)\n"),
+                )
+                .expect(
+                    ["list", "all", "custom"],
+                    RunOutput::exited(1).with_stderr(
+                        "Plugin custom's list-all callback script failed with output:\n\
+                         bash: /home/user/.asdf/plugins/custom/bin/list-all: No such file or directory\n",
+                    ),
+                )
+                .expect(
+                    ["list", "all", "rust"],
+                    RunOutput::exited(1).with_stderr(
+                        "Plugin rust's list-all callback script failed with output:\n\
+                         curl: (6) Could not resolve host: static.rust-lang.org\n",
+                    ),
+                ),
+        );
+
+        assert_eq!(
+            asdf.list_all("nodejs", Some("20.1")).unwrap(),
+            ["20.1.0", "20.10.0", "20.2.0"]
+        );
+        assert!(asdf.list_all("nodejs", Some("99")).unwrap().is_empty());
+        assert!(matches!(
+            asdf.list_all("custom", None::<&str>),
+            Err(Error::PluginScriptMissing { name, script: "list-all", .. }) if name == "custom"
+        ));
+        assert!(matches!(
+            asdf.list_all("rust", None::<&str>),
+            Err(Error::NoInternet(_))
+        ));
+    }
+
+    #[test]
+    fn plugin_remove_lists_versions_first() {
+        let runner = ReplayRunner::new()
+            .expect(
+                ["list", "nodejs"],
+                RunOutput::exited(0).with_stdout("  18.0.0\n *20.1.0\n"),
+            )
+            .expect(["plugin", "remove", "nodejs"], RunOutput::exited(0));
+        let asdf = client(runner.clone());
+
+        assert_eq!(asdf.plugin_remove("nodejs").unwrap(), ["18.0.0", "20.1.0"]);
+        assert!(runner.is_finished());
+    }
+
+    #[test]
+    fn plugin_update_all_continues_past_failures() {
+        let list = |nodejs_ref: &str| {
+            RunOutput::exited(0).with_stdout(format!(
+                "nodejs  https://github.com/asdf-vm/asdf-nodejs.git  master  {nodejs_ref}\n\
+                 rust    https://github.com/code-lever/asdf-rust.git  main  0d3c1f8\n"
+            ))
+        };
+        let runner = ReplayRunner::new()
+            .expect(["plugin", "list", "--urls", "--refs"], list("5f1e9b4"))
+            .expect(["plugin", "update", "nodejs"], RunOutput::exited(0))
+            .expect(
+                ["plugin", "update", "rust"],
+                RunOutput::exited(1).with_stderr("fatal: couldn't find remote ref main\n"),
+            )
+            .expect(["plugin", "list", "--urls", "--refs"], list("a7c3e21"));
+        let asdf = client(runner.clone());
+
+        let updates = asdf.plugin_update_all().unwrap();
+
+        assert!(runner.is_finished());
+
+        assert_eq!(updates.len(), 2);
+        let nodejs = updates[0].1.as_ref().unwrap();
+        assert_eq!(nodejs.previous_ref().as_deref(), Some("5f1e9b4"));
+        assert_eq!(nodejs.current_ref().as_deref(), Some("a7c3e21"));
+        assert!(nodejs.is_changed());
+        assert_eq!(updates[1].0, "rust");
+        assert!(matches!(updates[1].1, Err(Error::FailedCommand(_))));
+    }
+
+    #[test]
+    fn exec_returns_the_output_of_the_command() {
+        let asdf = client(
+            ReplayRunner::new()
+                .expect(
+                    ["exec", "node", "-e", "process.exit(3)"],
+                    RunOutput::exited(3).with_stdout(b"\xff\n".to_vec()),
+                )
+                .expect(
+                    ["exec", "curl", "https://example.com"],
+                    RunOutput::exited(6)
+                        .with_stderr("curl: (6) Could not resolve host: example.com\n"),
+                )
+                .expect(
+                    ["exec", "npx"],
+                    RunOutput::exited(126)
+                        .with_stderr("unknown command: npx. Perhaps you have to reshim?\n"),
+                ),
+        );
+
+        let output = asdf.exec("node", ["-e", "process.exit(3)"]).unwrap();
+        let offline = asdf.exec("curl", ["https://example.com"]).unwrap();
+
+        assert_eq!(output.code(), &Some(3));
+        assert_eq!(output.stdout(), b"\xff\n");
+        assert_eq!(offline.code(), &Some(6));
+        assert!(offline
+            .stderr()
+            .starts_with(b"curl: (6) Could not resolve host"));
+        assert!(matches!(
+            asdf.exec("npx", [] as [&str; 0]),
+            Err(Error::CommandNotFound { command, .. }) if command == "npx"
+        ));
+    }
+
+    #[test]
+    fn env_keeps_multiline_values() {
+        let asdf = client(ReplayRunner::new().expect(
+            ["env", "node", "env", "-0"],
+            RunOutput::exited(0).with_stdout(
+                "PATH=/home/user/.asdf/installs/nodejs/20.1.0/bin:/usr/bin\0\
+                 GREETING=hello\nworld\0EMPTY=\0",
+            ),
+        ));
+
+        let environment = asdf.env("node").unwrap();
+
+        assert_eq!(environment.len(), 3);
+        assert_eq!(environment["GREETING"], "hello\nworld");
+        assert_eq!(environment["EMPTY"], "");
+        assert!(environment["PATH"].starts_with("/home/user/.asdf/installs/nodejs"));
+    }
+
+    #[test]
+    fn info_parses_sections() {
+        let asdf = client(ReplayRunner::new().expect(
+            ["info"],
+            RunOutput::exited(0).with_stdout(
+                "OS:\nLinux host 6.1.0-18-amd64 #1 SMP x86_64 GNU/Linux\n\n\
+                 SHELL:\nGNU bash, version 5.2.15(1)-release (x86_64-pc-linux-gnu)\n\
+                 Copyright (C) 2022 Free Software Foundation, Inc.\n\n\
+                 BASH VERSION:\n5.2.15(1)-release\n\n\
+                 ASDF VERSION:\nv0.14.1-f00f759\n\n\
+                 ASDF INTERNAL VARIABLES:\nASDF_DEFAULT_TOOL_VERSIONS_FILENAME=.tool-versions\n\
+                 ASDF_DATA_DIR=/home/user/.asdf\n\n\
+                 ASDF INSTALLED PLUGINS:\n\
+                 nodejs                       https://github.com/asdf-vm/asdf-nodejs.git master 5f1e9b4\n\n",
+            ),
+        ));
+
+        let info = asdf.info().unwrap();
+
+        assert_eq!(
+            info.os(),
+            "Linux host 6.1.0-18-amd64 #1 SMP x86_64 GNU/Linux"
+        );
+        assert_eq!(info.shell_version().as_deref(), Some("5.2.15(1)-release"));
+        assert_eq!(info.bash_version().as_deref(), Some("5.2.15(1)-release"));
+        assert_eq!(info.asdf_version(), "v0.14.1-f00f759");
+        assert_eq!(info.variables().len(), 2);
+        assert_eq!(info.variables()["ASDF_DATA_DIR"], "/home/user/.asdf");
+        assert_eq!(info.plugins().len(), 1);
+        assert_eq!(info.plugins()[0].git_ref().as_deref(), Some("5f1e9b4"));
+    }
+
+    #[test]
+    fn info_without_plugins() {
+        let asdf = client(ReplayRunner::new().expect(
+            ["info"],
+            RunOutput::exited(0).with_stdout(
+                "OS:\nLinux\n\nSHELL:\nzsh 5.9\n\nASDF VERSION:\nv0.14.1\n\n\
+                 ASDF INSTALLED PLUGINS:\nNo plugins installed\n\n",
+            ),
+        ));
+
+        assert!(asdf.info().unwrap().plugins().is_empty());
+    }
+
+    #[test]
+    fn reshim_each_target() {
+        let runner = ReplayRunner::new()
+            .expect(["reshim"], RunOutput::exited(0))
+            .expect(["reshim", "nodejs", "20.1.0"], RunOutput::exited(0))
+            .expect(
+                ["reshim", "deno"],
+                RunOutput::exited(1).with_stderr("No such plugin: deno\n"),
+            );
+        let asdf = client(runner.clone()).with_data_dir("/nonexistent");
+
+        assert!(asdf.reshim(ReshimTarget::All).unwrap().is_empty());
+        assert!(asdf
+            .reshim(ReshimTarget::Version {
+                name: "nodejs".to_owned(),
+                version: "20.1.0".to_owned(),
+            })
+            .is_ok());
+        assert!(matches!(
+            asdf.reshim(ReshimTarget::Plugin("deno".to_owned())),
+            Err(Error::PluginNotFound { name, .. }) if name == "deno"
+        ));
+        assert!(runner.is_finished());
+    }
+
+    #[test]
+    fn shim_versions_by_asdf_version() {
+        let runner = ReplayRunner::new()
+            .expect(
+                ["shim-versions", "node"],
+                RunOutput::exited(0).with_stdout("nodejs 18.0.0\nnodejs 20.1.0\n"),
+            )
+            .expect(
+                ["shim-versions", "deno"],
+                RunOutput::exited(1).with_stdout("asdf: unknown shim deno\n"),
+            )
+            .expect(
+                ["shimversions", "node"],
+                RunOutput::exited(0).with_stdout("nodejs 20.1.0\n"),
+            );
+
+        let asdf = client(runner.clone());
+        assert_eq!(
+            asdf.shim_versions("node").unwrap(),
+            [
+                ("nodejs".to_owned(), "18.0.0".to_owned()),
+                ("nodejs".to_owned(), "20.1.0".to_owned()),
+            ]
+        );
+        assert!(matches!(
+            asdf.shim_versions("deno"),
+            Err(Error::CommandNotFound { command, .. }) if command == "deno"
+        ));
+
+        let asdf = client(runner.clone()).with_version(AsdfVersion::new(0, 16, 7));
+        assert_eq!(asdf.shim_versions("node").unwrap().len(), 1);
+        assert!(runner.is_finished());
+    }
+
+    #[test]
+    fn update_outcomes() {
+        let runner = ReplayRunner::new()
+            .expect(
+                ["--version"],
+                RunOutput::exited(0).with_stdout("v0.13.1-0b6cd2b\n"),
+            )
+            .expect(
+                ["update"],
+                RunOutput::exited(0).with_stdout("Updated asdf to release v0.14.1\n"),
+            )
+            .expect(
+                ["--version"],
+                RunOutput::exited(0).with_stdout("v0.14.1-f00f759\n"),
+            )
+            .expect(
+                ["--version"],
+                RunOutput::exited(0).with_stdout("v0.14.1-f00f759\n"),
+            )
+            .expect(
+                ["update"],
+                RunOutput::exited(0).with_stdout("Updated asdf to release v0.14.1\n"),
+            )
+            .expect(
+                ["--version"],
+                RunOutput::exited(0).with_stdout("v0.14.1-f00f759\n"),
+            )
+            .expect(
+                ["--version"],
+                RunOutput::exited(0).with_stdout("v0.14.1\n"),
+            )
+            .expect(
+                ["update", "--head"],
+                RunOutput::exited(42).with_stdout(
+                    "Update command disabled. Please use the package manager that you used to install asdf to upgrade asdf.\n",
+                ),
+            );
+        let asdf = client(runner.clone());
+
+        assert_eq!(
+            asdf.update(false).unwrap(),
+            UpdateOutcome::Updated {
+                from: "v0.13.1-0b6cd2b".to_owned(),
+                to: "v0.14.1-f00f759".to_owned(),
+            }
+        );
+        assert_eq!(
+            asdf.update(false).unwrap(),
+            UpdateOutcome::AlreadyLatest("v0.14.1-f00f759".to_owned())
+        );
+        assert!(matches!(
+            asdf.update(true).unwrap(),
+            UpdateOutcome::Unsupported(message) if message.starts_with("Update command disabled")
+        ));
+        assert!(runner.is_finished());
+    }
+
+    #[test]
+    fn commands_run_in_current_dir() {
+        let runner =
+            ReplayRunner::new().expect(["local", "nodejs", "20.1.0"], RunOutput::exited(0));
+        let asdf = client(runner.clone()).with_current_dir("/srv/project");
+
+        asdf.local("nodejs", "20.1.0").unwrap();
+
+        assert_eq!(
+            runner.invocations()[0].current_dir().as_deref(),
+            Some(std::path::Path::new("/srv/project"))
+        );
+    }
+
+    #[test]
+    fn set_maps_onto_local_and_global() {
+        let runner = ReplayRunner::new()
+            .expect(
+                ["local", "--parent", "nodejs", "20.1.0", "system"],
+                RunOutput::exited(0),
+            )
+            .expect(["global", "rust", "1.80.0"], RunOutput::exited(0));
+        let asdf = client(runner.clone());
+
+        asdf.set(Scope::Parent, "nodejs", ["20.1.0", "system"])
+            .unwrap();
+        asdf.global("rust", "1.80.0").unwrap();
+
+        assert!(runner.is_finished());
+    }
+
+    #[test]
+    fn set_uses_scope_flags() {
+        let runner = ReplayRunner::new()
+            .expect(["set", "--home", "nodejs", "20.1.0"], RunOutput::exited(0))
+            .expect(["set", "rust", "1.80.0"], RunOutput::exited(0));
+        let asdf = client(runner.clone()).with_version(AsdfVersion::new(0, 16, 7));
+
+        asdf.set(Scope::Home, "nodejs", ["20.1.0"]).unwrap();
+        asdf.local("rust", "1.80.0").unwrap();
+
+        assert!(runner.is_finished());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn plugin_list_all_async_strips_initialization() {
+        let asdf = client(ReplayRunner::new().expect(
+            ["plugin", "list", "all"],
+            RunOutput::exited(0).with_stdout(
+                "initializing plugin repository...\n\
+                 nodejs   https://github.com/asdf-vm/asdf-nodejs.git\n",
+            ),
+        ));
+
+        let plugins = asdf.plugin_list_all_async().await.unwrap();
+
+        assert_eq!(plugins.len(), 1);
+        assert_eq!(plugins[0].git_branch(), &None);
+    }
+}
@@ -0,0 +1,750 @@
+//! The table that decides which [`Error`] a command that did not succeed is reported as.
+//!
+//! Every command passes its output through [`check`] rather than matching messages itself,
+//! so that a message only has to be recognized in one place. Each [`Rule`] may be limited to
+//! certain commands and a range of `asdf` versions, because the messages differ between releases.
+
+use crate::{asdf::CommandResult, AsdfVersion, Error, Result};
+
+use Subcommand::*;
+
+/// The `asdf` command that produced a [`CommandResult`], which decides the rules that apply to it,
+/// and whether it can be run with a given version of `asdf`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Subcommand {
+    Version,
+    PluginAdd,
+    PluginList,
+    PluginListAll,
+    PluginRemove,
+    PluginUpdate,
+    Install,
+    Uninstall,
+    Current,
+    Where,
+    Which,
+    Latest,
+    LatestAll,
+    List,
+    ListAll,
+    Exec,
+    Env,
+    Info,
+    Reshim,
+    ShimVersions,
+    Update,
+    Set,
+    Local,
+    Global,
+}
+
+impl Subcommand {
+    /// The words that follow `asdf` to run this command, as shown by [`Error::Unsupported`].
+    fn name(self) -> &'static str {
+        match self {
+            Version => "--version",
+            PluginAdd => "plugin add",
+            PluginList => "plugin list",
+            PluginListAll => "plugin list all",
+            PluginRemove => "plugin remove",
+            PluginUpdate => "plugin update",
+            Install => "install",
+            Uninstall => "uninstall",
+            Current => "current",
+            Where => "where",
+            Which => "which",
+            Latest => "latest",
+            LatestAll => "latest --all",
+            List => "list",
+            ListAll => "list all",
+            Exec => "exec",
+            Env => "env",
+            Info => "info",
+            Reshim => "reshim",
+            ShimVersions => "shim-versions",
+            Update => "update",
+            Set => "set",
+            Local => "local",
+            Global => "global",
+        }
+    }
+
+    /// Whether the output is mostly that of another program, such as the command run by `asdf exec`,
+    /// so that only the rules naming this command apply to it.
+    fn runs_other_program(self) -> bool {
+        matches!(self, Exec | Env)
+    }
+
+    /// Return [`Error::Unsupported`] if `version` does not have this command.
+    pub(crate) fn require(self, version: AsdfVersion) -> Result<()> {
+        let supported = match self {
+            Set => version >= AsdfVersion::GO_REWRITE,
+            Local | Global => version < AsdfVersion::GO_REWRITE,
+            _ => true,
+        };
+
+        if supported {
+            Ok(())
+        } else {
+            Err(Error::Unsupported {
+                command: self.name(),
+                version,
+            })
+        }
+    }
+}
+
+/// The arguments of a command that an [`Error`] may name.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Subject<'a> {
+    pub(crate) name: Option<&'a str>,
+    pub(crate) version: Option<&'a str>,
+}
+
+impl<'a> Subject<'a> {
+    pub(crate) fn name(name: &'a str) -> Self {
+        Self {
+            name: Some(name),
+            version: None,
+        }
+    }
+}
+
+/// The [`Error`] variant that a [`Rule`] produces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Class {
+    /// Not an error, the message stands in for an empty list, see [`check_empty`].
+    Empty,
+    PluginNotFound,
+    PluginAlreadyAdded,
+    VersionNotFound,
+    VersionNotInstalled,
+    SystemVersion,
+    PluginScriptMissing(&'static str),
+    CommandVersionNotSet,
+    CommandNotFound,
+    NoVersionSet,
+    MalformedOptions,
+    NoInternet,
+    UpdateDisabled,
+}
+
+/// The exit codes that a [`Rule`] applies to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Status {
+    Any,
+    Failed,
+}
+
+/// The output that a [`Rule`] looks for its message in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Stream {
+    Stdout,
+    Stderr,
+    Any,
+}
+
+/// A message printed by a command, usually to standard error, and the [`Error`] that it means.
+#[derive(Clone, Copy, Debug)]
+struct Rule {
+    /// The message, where `{name}` stands for the name in the [`Subject`].
+    pattern: &'static str,
+    class: Class,
+    /// The commands that print this message, or all of them if empty,
+    /// except those whose output is another program's (see [`Subcommand::runs_other_program`]).
+    commands: &'static [Subcommand],
+    /// The first version that prints this message.
+    since: Option<AsdfVersion>,
+    /// The first version that no longer prints this message.
+    until: Option<AsdfVersion>,
+    status: Status,
+    stream: Stream,
+}
+
+impl Rule {
+    const fn new(pattern: &'static str, class: Class) -> Self {
+        Self {
+            pattern,
+            class,
+            commands: &[],
+            since: None,
+            until: None,
+            status: Status::Any,
+            stream: Stream::Stderr,
+        }
+    }
+
+    const fn commands(mut self, commands: &'static [Subcommand]) -> Self {
+        self.commands = commands;
+        self
+    }
+
+    const fn since(mut self, version: AsdfVersion) -> Self {
+        self.since = Some(version);
+        self
+    }
+
+    const fn until(mut self, version: AsdfVersion) -> Self {
+        self.until = Some(version);
+        self
+    }
+
+    const fn failed(mut self) -> Self {
+        self.status = Status::Failed;
+        self
+    }
+
+    /// Look for the message in standard output rather than standard error.
+    const fn stdout(mut self) -> Self {
+        self.stream = Stream::Stdout;
+        self
+    }
+
+    /// Look for the message in both standard output and standard error.
+    const fn any_stream(mut self) -> Self {
+        self.stream = Stream::Any;
+        self
+    }
+
+    /// Whether this rule applies to `result`. When the version of `asdf` is not known,
+    /// rules for every version apply.
+    fn matches(&self, subject: Subject<'_>, result: &CommandResult) -> bool {
+        (if self.commands.is_empty() {
+            !result.subcommand.runs_other_program()
+        } else {
+            self.commands.contains(&result.subcommand)
+        }) && result.version.is_none_or(|version| {
+            self.since.is_none_or(|since| version >= since)
+                && self.until.is_none_or(|until| version < until)
+        }) && (self.status == Status::Any || !result.output.success())
+            && match self.stream {
+                Stream::Stdout => self.contained_in(subject, &result.stdout),
+                Stream::Stderr => self.contained_in(subject, &result.stderr),
+                Stream::Any => {
+                    self.contained_in(subject, &result.stdout)
+                        || self.contained_in(subject, &result.stderr)
+                }
+            }
+    }
+
+    /// Whether `output` contains the pattern. A pattern with `{name}` never matches without a name.
+    fn contained_in(&self, subject: Subject<'_>, output: &str) -> bool {
+        if !self.pattern.contains("{name}") {
+            return output.contains(self.pattern);
+        }
+
+        subject
+            .name
+            .is_some_and(|name| output.contains(&self.pattern.replace("{name}", name)))
+    }
+}
+
+/// Every message that is known to mean something more specific than [`Error::FailedCommand`],
+/// the first rule that matches is used.
+const RULES: &[Rule] = &[
+    Rule::new("not found in repository", Class::PluginNotFound).commands(&[PluginAdd]),
+    Rule::new("already added", Class::PluginAlreadyAdded).commands(&[PluginAdd]),
+    Rule::new(
+        "bin/list-all: No such file",
+        Class::PluginScriptMissing("list-all"),
+    )
+    .until(AsdfVersion::GO_REWRITE),
+    Rule::new(
+        "does not have a callback named list-all",
+        Class::PluginScriptMissing("list-all"),
+    )
+    .since(AsdfVersion::GO_REWRITE),
+    Rule::new("No such plugin", Class::PluginNotFound),
+    Rule::new("Plugin named {name} not installed", Class::PluginNotFound)
+        .commands(&[
+            Install,
+            Uninstall,
+            Current,
+            Where,
+            Latest,
+            List,
+            ListAll,
+            PluginRemove,
+            PluginUpdate,
+            Set,
+            Reshim,
+        ])
+        .since(AsdfVersion::GO_REWRITE),
+    Rule::new("No compatible versions available", Class::VersionNotFound),
+    Rule::new("No such version", Class::VersionNotInstalled).commands(&[Uninstall]),
+    Rule::new("Version not installed", Class::VersionNotInstalled).commands(&[Where]),
+    Rule::new("System version is selected", Class::SystemVersion).commands(&[Where]),
+    Rule::new("No version is set for command", Class::CommandVersionNotSet)
+        .commands(&[Which, Exec, Env]),
+    Rule::new("unknown command:", Class::CommandNotFound).commands(&[Which, Exec, Env]),
+    Rule::new("unknown shim", Class::CommandNotFound)
+        .commands(&[ShimVersions])
+        .failed()
+        .stdout()
+        .until(AsdfVersion::GO_REWRITE),
+    Rule::new("unknown shim", Class::CommandNotFound)
+        .commands(&[ShimVersions])
+        .failed(),
+    Rule::new("No version is set", Class::NoVersionSet),
+    Rule::new("No versions specified", Class::NoVersionSet).until(AsdfVersion::GO_REWRITE),
+    Rule::new("no version set", Class::NoVersionSet).since(AsdfVersion::GO_REWRITE),
+    // Printed with code 42 when `asdf` was not installed with a Git clone.
+    Rule::new("Update command disabled", Class::UpdateDisabled)
+        .commands(&[Update])
+        .stdout()
+        .until(AsdfVersion::GO_REWRITE),
+    Rule::new("usage: asdf", Class::MalformedOptions).until(AsdfVersion::GO_REWRITE),
+    Rule::new("Incorrect Usage", Class::MalformedOptions).since(AsdfVersion::GO_REWRITE),
+    Rule::new("Could not resolve host", Class::NoInternet).failed(),
+    Rule::new("Temporary failure in name resolution", Class::NoInternet).failed(),
+    Rule::new("No plugins installed", Class::Empty)
+        .commands(&[PluginList, List, Info])
+        .any_stream(),
+    Rule::new("No versions installed", Class::Empty)
+        .commands(&[List])
+        .any_stream(),
+    Rule::new("No compatible versions installed", Class::Empty)
+        .commands(&[List])
+        .any_stream(),
+];
+
+/// The [`Error`] that `result` is reported as, if any rule matches it.
+pub(crate) fn classify(subject: Subject<'_>, result: &CommandResult) -> Option<Error> {
+    let rule = rule(subject, result)?;
+    let name = || subject.name.unwrap_or_default().to_owned();
+    let version = || subject.version.unwrap_or_default().to_owned();
+    let failure = result.failure();
+
+    Some(match rule.class {
+        Class::Empty => return None,
+        Class::PluginNotFound => Error::PluginNotFound {
+            name: name(),
+            failure,
+        },
+        Class::PluginAlreadyAdded => Error::PluginAlreadyAdded {
+            name: name(),
+            failure,
+        },
+        Class::VersionNotFound => Error::VersionNotFound {
+            name: name(),
+            version: version(),
+            failure,
+        },
+        Class::VersionNotInstalled => Error::VersionNotInstalled {
+            name: name(),
+            version: subject.version.map(str::to_owned),
+            failure,
+        },
+        Class::SystemVersion => Error::SystemVersion {
+            name: name(),
+            failure,
+        },
+        Class::PluginScriptMissing(script) => Error::PluginScriptMissing {
+            name: name(),
+            script,
+            failure,
+        },
+        Class::CommandVersionNotSet => Error::CommandVersionNotSet {
+            command: name(),
+            versions: plugin_versions(&result.stderr),
+            failure,
+        },
+        Class::CommandNotFound => Error::CommandNotFound {
+            command: name(),
+            failure,
+        },
+        Class::NoVersionSet => Error::NoVersionSet {
+            name: name(),
+            failure,
+        },
+        Class::MalformedOptions => Error::MalformedOptions(failure),
+        Class::NoInternet => Error::NoInternet(failure),
+        Class::UpdateDisabled => Error::UpdateDisabled(failure),
+    })
+}
+
+/// The first rule that applies to `result`.
+fn rule(subject: Subject<'_>, result: &CommandResult) -> Option<&'static Rule> {
+    RULES.iter().find(|rule| rule.matches(subject, result))
+}
+
+/// The same as [`check`], but returns whether `asdf` printed a message in place of an empty list,
+/// such as `No plugins installed`, which is not an error even if the command did not succeed.
+pub(crate) fn check_empty(subject: Subject<'_>, result: &CommandResult) -> Result<bool> {
+    match rule(subject, result) {
+        Some(rule) if rule.class == Class::Empty => Ok(true),
+        _ => check(subject, result).map(|()| false),
+    }
+}
+
+/// Whether `line` from the output of `subcommand` is a message in place of an empty list,
+/// rather than an item of the list.
+pub(crate) fn is_empty_message(subcommand: Subcommand, line: &str) -> bool {
+    RULES
+        .iter()
+        .filter(|rule| rule.class == Class::Empty && rule.commands.contains(&subcommand))
+        .any(|rule| line.contains(rule.pattern))
+}
+
+/// The plugins and versions listed after a message, one per line as `<name> <version>`.
+fn plugin_versions(message: &str) -> Vec<(String, String)> {
+    message
+        .lines()
+        .filter_map(|line| {
+            let mut words = line.split_whitespace();
+
+            match (words.next(), words.next(), words.next()) {
+                (Some(name), Some(version), None) => Some((name.to_owned(), version.to_owned())),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Return the [`Error`] that `result` is reported as, or [`Error::FailedCommand`]
+/// if no rule matches and the command did not succeed.
+pub(crate) fn check(subject: Subject<'_>, result: &CommandResult) -> Result<()> {
+    match classify(subject, result) {
+        Some(error) => Err(error),
+        None if result.output.success() => Ok(()),
+        None => Err(Error::FailedCommand(result.failure())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::{Invocation, RunOutput};
+
+    fn result(
+        subcommand: Subcommand,
+        version: Option<AsdfVersion>,
+        code: i32,
+        stderr: &str,
+    ) -> CommandResult {
+        CommandResult::new(
+            subcommand,
+            Invocation::new("asdf"),
+            RunOutput::exited(code).with_stderr(stderr),
+            version,
+        )
+        .unwrap()
+    }
+
+    fn result_stdout(
+        subcommand: Subcommand,
+        version: Option<AsdfVersion>,
+        code: i32,
+        stdout: &str,
+    ) -> CommandResult {
+        CommandResult::new(
+            subcommand,
+            Invocation::new("asdf"),
+            RunOutput::exited(code).with_stdout(stdout),
+            version,
+        )
+        .unwrap()
+    }
+
+    const BASH: Option<AsdfVersion> = Some(AsdfVersion::new(0, 14, 1));
+    const GO: Option<AsdfVersion> = Some(AsdfVersion::new(0, 16, 7));
+
+    #[test]
+    fn plugin_not_found_in_repository() {
+        let result = result(
+            PluginAdd,
+            BASH,
+            1,
+            "plugin nodejz not found in repository\n",
+        );
+
+        assert!(matches!(
+            classify(Subject::name("nodejz"), &result),
+            Some(Error::PluginNotFound { name, .. }) if name == "nodejz"
+        ));
+    }
+
+    #[test]
+    fn plugin_already_added() {
+        let result = result(PluginAdd, GO, 2, "Plugin named nodejs already added\n");
+
+        assert!(matches!(
+            classify(Subject::name("nodejs"), &result),
+            Some(Error::PluginAlreadyAdded { name, .. }) if name == "nodejs"
+        ));
+    }
+
+    #[test]
+    fn no_such_plugin() {
+        let result = result(Local, BASH, 1, "No such plugin: nodejz\n");
+
+        assert!(matches!(
+            classify(Subject::name("nodejz"), &result),
+            Some(Error::PluginNotFound { name, .. }) if name == "nodejz"
+        ));
+    }
+
+    #[test]
+    fn plugin_named_not_installed_since_go_rewrite() {
+        let message = "Plugin named nodejz not installed\n";
+
+        assert!(matches!(
+            classify(Subject::name("nodejz"), &result(Install, GO, 1, message)),
+            Some(Error::PluginNotFound { name, .. }) if name == "nodejz"
+        ));
+        assert!(classify(Subject::name("nodejz"), &result(Install, BASH, 1, message)).is_none());
+        assert!(classify(Subject::name("nodejs"), &result(Install, GO, 1, message)).is_none());
+        assert!(classify(Subject::default(), &result(Install, GO, 1, message)).is_none());
+        assert!(classify(Subject::name("nodejz"), &result(Exec, GO, 1, message)).is_none());
+    }
+
+    #[test]
+    fn no_version_set_differs_between_versions() {
+        let bash = "No versions specified for nodejs in config files or environment\n";
+        let go = "no version set for plugin nodejs\n";
+
+        assert!(matches!(
+            classify(Subject::name("nodejs"), &result(Install, BASH, 1, bash)),
+            Some(Error::NoVersionSet { name, .. }) if name == "nodejs"
+        ));
+        assert!(matches!(
+            classify(Subject::name("nodejs"), &result(Install, GO, 1, go)),
+            Some(Error::NoVersionSet { name, .. }) if name == "nodejs"
+        ));
+        assert!(classify(Subject::name("nodejs"), &result(Install, BASH, 1, go)).is_none());
+        assert!(classify(Subject::name("nodejs"), &result(Install, GO, 1, bash)).is_none());
+    }
+
+    #[test]
+    fn version_not_found() {
+        let result = result(
+            Install,
+            BASH,
+            1,
+            "No compatible versions available (nodejs 99)\n",
+        );
+        let subject = Subject {
+            name: Some("nodejs"),
+            version: Some("latest:99"),
+        };
+
+        assert!(matches!(
+            classify(subject, &result),
+            Some(Error::VersionNotFound { name, version, .. })
+                if name == "nodejs" && version == "latest:99"
+        ));
+    }
+
+    #[test]
+    fn removed_commands_are_unsupported() {
+        assert!(Local.require(AsdfVersion::new(0, 15, 0)).is_ok());
+        assert!(Set.require(AsdfVersion::new(0, 15, 0)).is_err());
+        assert!(Set.require(AsdfVersion::GO_REWRITE).is_ok());
+        assert!(matches!(
+            Global.require(AsdfVersion::GO_REWRITE),
+            Err(Error::Unsupported {
+                command: "global",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn plugin_messages_only_apply_to_plugin_add() {
+        let result = result(
+            PluginList,
+            BASH,
+            1,
+            "plugin nodejz not found in repository\n",
+        );
+
+        assert!(classify(Subject::default(), &result).is_none());
+    }
+
+    #[test]
+    fn usage_differs_between_versions() {
+        let bash = result(
+            PluginAdd,
+            BASH,
+            1,
+            "usage: asdf plugin add <name> [<git-url>]\n",
+        );
+        let go = result(
+            PluginAdd,
+            GO,
+            1,
+            "Incorrect Usage: flag provided but not defined: -x\n",
+        );
+
+        assert!(matches!(
+            classify(Subject::default(), &bash),
+            Some(Error::MalformedOptions(_))
+        ));
+        assert!(matches!(
+            classify(Subject::default(), &go),
+            Some(Error::MalformedOptions(_))
+        ));
+        assert!(classify(Subject::default(), &result(PluginAdd, GO, 1, "usage: asdf")).is_none());
+    }
+
+    #[test]
+    fn unknown_version_uses_every_rule() {
+        let result = result(
+            PluginAdd,
+            None,
+            1,
+            "Incorrect Usage: flag provided but not defined\n",
+        );
+
+        assert!(matches!(
+            classify(Subject::default(), &result),
+            Some(Error::MalformedOptions(_))
+        ));
+    }
+
+    #[test]
+    fn no_internet_requires_failure() {
+        let message = "fatal: unable to access 'https://github.com/asdf-vm/asdf-plugins.git/': \
+                       Could not resolve host: github.com\n";
+
+        assert!(matches!(
+            classify(
+                Subject::default(),
+                &result(PluginListAll, BASH, 128, message)
+            ),
+            Some(Error::NoInternet(_))
+        ));
+        assert!(classify(Subject::default(), &result(PluginListAll, BASH, 0, message)).is_none());
+    }
+
+    #[test]
+    fn no_internet_on_name_resolution_failure() {
+        let message = "fatal: unable to access 'https://github.com/asdf-vm/asdf-plugins.git/': \
+                       Temporary failure in name resolution\n";
+
+        for version in [BASH, GO] {
+            assert!(matches!(
+                classify(
+                    Subject::default(),
+                    &result(PluginListAll, version, 128, message)
+                ),
+                Some(Error::NoInternet(_))
+            ));
+            assert!(classify(
+                Subject::default(),
+                &result(PluginListAll, version, 0, message)
+            )
+            .is_none());
+        }
+    }
+
+    #[test]
+    fn missing_list_all_script_differs_between_versions() {
+        let bash = "/home/user/.asdf/plugins/custom/bin/list-all: No such file or directory\n";
+        let go = "Plugin named custom does not have a callback named list-all\n";
+
+        for (version, message) in [(BASH, bash), (GO, go)] {
+            assert!(matches!(
+                classify(Subject::name("custom"), &result(ListAll, version, 1, message)),
+                Some(Error::PluginScriptMissing { name, script: "list-all", .. }) if name == "custom"
+            ));
+        }
+        assert!(classify(Subject::name("custom"), &result(ListAll, BASH, 1, go)).is_none());
+        assert!(classify(Subject::name("custom"), &result(ListAll, GO, 1, bash)).is_none());
+    }
+
+    #[test]
+    fn unknown_shim_on_stdout() {
+        let message = "asdf: unknown shim deno\n";
+
+        assert!(matches!(
+            classify(Subject::name("deno"), &result_stdout(ShimVersions, BASH, 1, message)),
+            Some(Error::CommandNotFound { command, .. }) if command == "deno"
+        ));
+        assert!(classify(
+            Subject::name("deno"),
+            &result_stdout(ShimVersions, GO, 1, message)
+        )
+        .is_none());
+        assert!(matches!(
+            classify(
+                Subject::name("deno"),
+                &result(ShimVersions, GO, 1, "unknown shim deno\n")
+            ),
+            Some(Error::CommandNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn update_disabled_on_stdout() {
+        let message = "Update command disabled. Please use the package manager that you used \
+                       to install asdf to upgrade asdf.\n";
+
+        assert!(matches!(
+            classify(
+                Subject::default(),
+                &result_stdout(Update, BASH, 42, message)
+            ),
+            Some(Error::UpdateDisabled(_))
+        ));
+        assert!(classify(Subject::default(), &result(Update, BASH, 42, message)).is_none());
+        assert!(classify(Subject::default(), &result_stdout(Exec, BASH, 42, message)).is_none());
+    }
+
+    #[test]
+    fn exec_only_matches_its_own_messages() {
+        let message = "curl: (6) Could not resolve host: example.com\n";
+
+        assert!(classify(Subject::name("curl"), &result(Exec, BASH, 6, message)).is_none());
+        assert!(classify(
+            Subject::name("node"),
+            &result(Exec, BASH, 1, "No version is set\n")
+        )
+        .is_none());
+        assert!(matches!(
+            classify(
+                Subject::name("npx"),
+                &result(
+                    Exec,
+                    BASH,
+                    126,
+                    "unknown command: npx. Perhaps you have to reshim?\n"
+                )
+            ),
+            Some(Error::CommandNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn empty_messages_are_not_errors() {
+        let message = "No versions installed\n";
+
+        assert!(classify(Subject::name("rust"), &result(List, BASH, 1, message)).is_none());
+        assert!(check_empty(Subject::name("rust"), &result(List, BASH, 1, message)).unwrap());
+        assert!(check_empty(
+            Subject::default(),
+            &result_stdout(List, BASH, 0, "No plugins installed\n")
+        )
+        .unwrap());
+        assert!(!check_empty(Subject::default(), &result(List, BASH, 0, "")).unwrap());
+        assert!(matches!(
+            check_empty(Subject::name("rust"), &result(Uninstall, BASH, 1, message)),
+            Err(Error::FailedCommand(_))
+        ));
+        assert!(is_empty_message(List, "  No versions installed"));
+        assert!(!is_empty_message(List, "  20.1.0"));
+        assert!(!is_empty_message(PluginListAll, "No plugins installed"));
+    }
+
+    #[test]
+    fn check_falls_back_to_failed_command() {
+        assert!(check(Subject::default(), &result(PluginList, BASH, 0, "")).is_ok());
+        assert!(matches!(
+            check(
+                Subject::default(),
+                &result(PluginList, BASH, 1, "something broke\n")
+            ),
+            Err(Error::FailedCommand(_))
+        ));
+    }
+}
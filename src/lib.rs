@@ -10,6 +10,28 @@
 //! - Due to `where` being a Rust keyword, the `asdf which` command is provided by [`asdf::packages::locate`].
 //!   - This is unless you use the flat API, in which case it is re-exported to `asdf::prelude::asdf_where`.
 //!
+//! ## Client
+//!
+//! Every command is also a method on [`asdf::Asdf`], which allows choosing the `asdf` executable and the
+//! `ASDF_*` environment variables it is run with. The free functions use a default client,
+//! which runs `asdf` from `PATH` with the environment and working directory of the current process.
+//! Use [`asdf::Asdf::with_current_dir`] to run commands against the `.tool-versions` of another project.
+//!
+//! The version of `asdf` is detected with `asdf --version` when the first command is run (see [`asdf::Asdf::version`]),
+//! so that both the releases written in Bash and the rewrite in Go (`0.16.0` and later) are supported.
+//! A command that does not exist in the detected version returns [`asdf::Error::Unsupported`].
+//!
+//! ## Asynchronous API
+//!
+//! With the `tokio` feature enabled, every command that is implemented has an `_async` counterpart on
+//! [`asdf::Asdf`], and the module `asdf::nonblocking` mirrors the free functions of [`asdf::plugins`],
+//! [`asdf::packages`] and [`asdf::utils`]. These run `asdf` with `tokio::process` rather than blocking.
+//!
+//! ## Serialization
+//!
+//! With the `serde` feature enabled, [`asdf::utils::AsdfInfo`] and [`asdf::plugins::Plugin`] implement
+//! `Serialize` and `Deserialize`, so that the output of [`asdf::utils::info`] can be stored as JSON.
+//!
 //! # Commands and Exports
 //!
 //! The content on the previously referenced page is copied (with visual separation) here for your convenience.
@@ -42,9 +64,11 @@
 //! | `asdf local <name> latest[:<version>]`   <br/><sub>[`asdf::packages::local`],<br/>`asdf::prelude::asdf_local`</sub>           | Set the package local version to the latest provided version                                                                                  |
 //! | `asdf global <name> <version>`           <br/><sub>[`asdf::packages::global`],<br/>`asdf::prelude::asdf_global`</sub>         | Set the package global version                                                                                                                |
 //! | `asdf global <name> latest[:<version>]`  <br/><sub>[`asdf::packages::global`],<br/>`asdf::prelude::asdf_global`</sub>         | Set the package global version to the latest provided version                                                                                 |
+//! | `asdf set [--home\|--parent] <name> <version...>` <br/><sub>[`asdf::packages::set`],<br/>`asdf::prelude::asdf_set`</sub> | Set the package version in the `.tool-versions` of the current, a parent, or the home directory (replaces `local` and `global` since 0.16.0) |
 //! | `asdf shell <name> <version>`            <br/><sub>[`asdf::packages::shell`],<br/>`asdf::prelude::asdf_shell`</sub>           | Set the package version to `ASDF_${LANG}_VERSION` in the current shell                                                                        |
 //! | `asdf latest <name> [<version>]`         <br/><sub>[`asdf::packages::latest`],<br/>`asdf::prelude::asdf_latest`</sub>         | Show latest stable version of a package                                                                                                       |
 //! | `asdf latest --all`                      <br/><sub>[`asdf::packages::latest_all`],<br/>`asdf::prelude::asdf_latest_all`</sub> | Show latest stable version of all the packages and if they are installed                                                                      |
+//! | `asdf list`                              <br/><sub>[`asdf::packages::list`],<br/>`asdf::prelude::asdf_list`</sub>             | List installed versions of every package                                                                                                      |
 //! | `asdf list <name> [version]`             <br/><sub>[`asdf::packages::list`],<br/>`asdf::prelude::asdf_list`</sub>             | List installed versions of a package and optionally filter the versions                                                                       |
 //! | `asdf list all <name> [<version>]`       <br/><sub>[`asdf::packages::list_all`],<br/>`asdf::prelude::asdf_list_all`</sub>     | List all versions of a package and optionally filter the returned versions                                                                    |
 //! | `asdf help <name> [<version>]`           <br/><sub>[`asdf::utils::help`],<br/>`asdf::prelude::asdf_help`</sub>                | Output documentation for plugin and tool                                                                                                      |
@@ -63,14 +87,22 @@
 //! | `asdf update --head`            <br/><sub>[`asdf::utils::update`],<br/>`asdf::prelude::asdf_update`</sub>               | Update asdf to the latest on the master branch                                    |
 
 mod asdf;
+mod classify;
+#[cfg(feature = "tokio")]
+pub mod nonblocking;
+pub mod runner;
+mod version;
 
 pub use self::asdf::*;
+pub use self::version::*;
 
 /// The 'prelude' module can be imported as `use asdf::prelude::*` if you want access to a flat API.
 /// The functions are renamed according to the corresponding command, formatted in `snake_case`.
 pub mod prelude {
     use super::*;
 
+    pub use super::Asdf;
+
     pub use plugins::add as asdf_plugin_add;
     pub use plugins::list as asdf_plugin_list;
     pub use plugins::list_all as asdf_plugin_list_all;
@@ -87,6 +119,7 @@ pub mod prelude {
     pub use packages::list_all as asdf_list_all;
     pub use packages::local as asdf_local;
     pub use packages::locate as asdf_where;
+    pub use packages::set as asdf_set;
     pub use packages::shell as asdf_shell;
     pub use packages::uninstall as asdf_uninstall;
     pub use packages::which as asdf_which;
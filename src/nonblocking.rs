@@ -0,0 +1,221 @@
+//! Asynchronous counterparts of the free functions in [`plugins`](crate::plugins),
+//! [`packages`](crate::packages) and [`utils`](crate::utils), available with the `tokio` feature.
+//!
+//! Each function uses the same default client as its blocking counterpart, and returns the same
+//! [`Result`]. To use a configured client, call the `_async` methods on [`Asdf`](crate::Asdf) instead.
+
+use crate::asdf::default_client;
+
+/// MANAGE PLUGINS
+pub mod plugins {
+    use super::*;
+    use crate::{
+        plugins::{PluginSet, PluginUpdate},
+        Result,
+    };
+
+    /// See [`crate::plugins::add`].
+    pub async fn add<A, B>(name: A, git_url: Option<B>) -> Result<()>
+    where
+        A: AsRef<str>,
+        B: AsRef<str>,
+    {
+        default_client().plugin_add_async(name, git_url).await
+    }
+
+    /// See [`crate::plugins::list`].
+    pub async fn list() -> Result<PluginSet> {
+        default_client().plugin_list_async().await
+    }
+
+    /// See [`crate::plugins::list_all`].
+    pub async fn list_all() -> Result<PluginSet> {
+        default_client().plugin_list_all_async().await
+    }
+
+    /// See [`crate::plugins::remove`].
+    pub async fn remove<A>(name: A) -> Result<Vec<String>>
+    where
+        A: AsRef<str>,
+    {
+        default_client().plugin_remove_async(name).await
+    }
+
+    /// See [`crate::plugins::update`].
+    pub async fn update<A, B>(name: A, git_ref: Option<B>) -> Result<PluginUpdate>
+    where
+        A: AsRef<str>,
+        B: AsRef<str>,
+    {
+        default_client().plugin_update_async(name, git_ref).await
+    }
+
+    /// See [`crate::plugins::update_all`].
+    pub async fn update_all() -> Result<Vec<(String, Result<PluginUpdate>)>> {
+        default_client().plugin_update_all_async().await
+    }
+}
+
+/// MANAGE PACKAGES
+pub mod packages {
+    use super::*;
+    use crate::{
+        packages::{
+            CurrentVersion, InstallSpec, Installation, InstalledVersions, LatestVersion, Scope,
+            Uninstallation,
+        },
+        Result,
+    };
+    use std::path::PathBuf;
+
+    /// See [`crate::packages::install`].
+    pub async fn install(spec: InstallSpec) -> Result<Vec<(String, Result<Installation>)>> {
+        default_client().install_async(spec).await
+    }
+
+    /// See [`crate::packages::uninstall`].
+    pub async fn uninstall<A, B>(name: A, version: B) -> Result<Uninstallation>
+    where
+        A: AsRef<str>,
+        B: AsRef<str>,
+    {
+        default_client().uninstall_async(name, version).await
+    }
+
+    /// See [`crate::packages::current`].
+    pub async fn current<A>(name: Option<A>) -> Result<Vec<CurrentVersion>>
+    where
+        A: AsRef<str>,
+    {
+        default_client().current_async(name).await
+    }
+
+    /// See [`crate::packages::locate`].
+    pub async fn locate<A, B>(name: A, version: Option<B>) -> Result<PathBuf>
+    where
+        A: AsRef<str>,
+        B: AsRef<str>,
+    {
+        default_client().locate_async(name, version).await
+    }
+
+    /// See [`crate::packages::which`].
+    pub async fn which<A>(command: A) -> Result<PathBuf>
+    where
+        A: AsRef<str>,
+    {
+        default_client().which_async(command).await
+    }
+
+    /// See [`crate::packages::latest`].
+    pub async fn latest<A, B>(name: A, prefix: Option<B>) -> Result<String>
+    where
+        A: AsRef<str>,
+        B: AsRef<str>,
+    {
+        default_client().latest_async(name, prefix).await
+    }
+
+    /// See [`crate::packages::latest_all`].
+    pub async fn latest_all() -> Result<Vec<LatestVersion>> {
+        default_client().latest_all_async().await
+    }
+
+    /// See [`crate::packages::list`].
+    pub async fn list<A, B>(name: Option<A>, filter: Option<B>) -> Result<InstalledVersions>
+    where
+        A: AsRef<str>,
+        B: AsRef<str>,
+    {
+        default_client().list_async(name, filter).await
+    }
+
+    /// See [`crate::packages::list_all`].
+    pub async fn list_all<A, B>(name: A, prefix: Option<B>) -> Result<Vec<String>>
+    where
+        A: AsRef<str>,
+        B: AsRef<str>,
+    {
+        default_client().list_all_async(name, prefix).await
+    }
+
+    /// See [`crate::packages::set`].
+    pub async fn set<A, B, C>(scope: Scope, name: A, versions: C) -> Result<()>
+    where
+        A: AsRef<str>,
+        B: AsRef<str>,
+        C: IntoIterator<Item = B>,
+    {
+        default_client().set_async(scope, name, versions).await
+    }
+
+    /// See [`crate::packages::local`].
+    pub async fn local<A, B>(name: A, version: B) -> Result<()>
+    where
+        A: AsRef<str>,
+        B: AsRef<str>,
+    {
+        default_client().local_async(name, version).await
+    }
+
+    /// See [`crate::packages::global`].
+    pub async fn global<A, B>(name: A, version: B) -> Result<()>
+    where
+        A: AsRef<str>,
+        B: AsRef<str>,
+    {
+        default_client().global_async(name, version).await
+    }
+}
+
+/// UTILS
+pub mod utils {
+    use super::*;
+    use crate::{
+        runner::RunOutput,
+        utils::{AsdfInfo, Environment, ReshimTarget, ShimChanges, UpdateOutcome},
+        Result,
+    };
+    use std::ffi::OsStr;
+
+    /// See [`crate::utils::exec`].
+    pub async fn exec<A, B, C>(command: A, args: C) -> Result<RunOutput>
+    where
+        A: AsRef<OsStr>,
+        B: AsRef<OsStr>,
+        C: IntoIterator<Item = B>,
+    {
+        default_client().exec_async(command, args).await
+    }
+
+    /// See [`crate::utils::env()`].
+    pub async fn env<A>(command: A) -> Result<Environment>
+    where
+        A: AsRef<str>,
+    {
+        default_client().env_async(command).await
+    }
+
+    /// See [`crate::utils::info`].
+    pub async fn info() -> Result<AsdfInfo> {
+        default_client().info_async().await
+    }
+
+    /// See [`crate::utils::reshim`].
+    pub async fn reshim(target: ReshimTarget) -> Result<ShimChanges> {
+        default_client().reshim_async(target).await
+    }
+
+    /// See [`crate::utils::shim_versions`].
+    pub async fn shim_versions<A>(command: A) -> Result<Vec<(String, String)>>
+    where
+        A: AsRef<str>,
+    {
+        default_client().shim_versions_async(command).await
+    }
+
+    /// See [`crate::utils::update`].
+    pub async fn update(head: bool) -> Result<UpdateOutcome> {
+        default_client().update_async(head).await
+    }
+}
@@ -0,0 +1,1003 @@
+//! The [`Runner`] trait is what an [`Asdf`](crate::Asdf) client uses to start processes.
+//!
+//! By default, [`SystemRunner`] is used, which runs the command with [`std::process::Command`].
+//! To test code that uses this library without `asdf` installed, use a [`ReplayRunner`] with
+//! canned responses, or implement [`Runner`] yourself.
+//!
+//! An [`Invocation`] may have a timeout or a [`CancelHandle`], in which case the runner is expected to kill
+//! the process (and anything it started) when either is triggered, and report it through [`RunOutput::interrupted`].
+//!
+//! When a client has an output handler, [`Runner::run_streaming`] is used instead of [`Runner::run`],
+//! which reports each line as an [`OutputEvent`] while the process is running.
+//!
+//! With the `tokio` feature enabled, `Runner::run_async` and `Runner::run_streaming_async`
+//! are used by the asynchronous API.
+
+use std::{
+    collections::VecDeque,
+    ffi::{OsStr, OsString},
+    fmt::Debug,
+    io::{BufRead, BufReader, Read},
+    path::PathBuf,
+    process::{Child, Command, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use getset::Getters;
+
+/// A boxed [`Future`](std::future::Future) as returned by [`Runner::run_async`].
+#[cfg(feature = "tokio")]
+pub type BoxFuture<'a, T> = std::pin::Pin<Box<dyn std::future::Future<Output = T> + Send + 'a>>;
+
+/// A line written by a running process, without the line terminator.
+///
+/// Invalid UTF-8 is replaced lossily, the bytes in [`RunOutput`] are left untouched.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputEvent<'a> {
+    /// A line written to standard output.
+    Stdout(&'a str),
+    /// A line written to standard error.
+    Stderr(&'a str),
+}
+
+/// The stream that a line was read from, used to build an [`OutputEvent`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Stream {
+    Stdout,
+    Stderr,
+}
+
+impl Stream {
+    fn event(self, line: &[u8], on_output: &mut dyn FnMut(OutputEvent<'_>)) {
+        let text = String::from_utf8_lossy(line);
+        let text = text.trim_end_matches('\n').trim_end_matches('\r');
+
+        on_output(match self {
+            Stream::Stdout => OutputEvent::Stdout(text),
+            Stream::Stderr => OutputEvent::Stderr(text),
+        });
+    }
+}
+
+/// How often a running process is checked for a timeout or cancellation.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A handle that can be used to kill a running command from another thread or task.
+/// Clones share the same state, and once cancelled a handle stays cancelled.
+///
+/// Give a handle to a client with [`Asdf::with_cancel_handle`](crate::Asdf::with_cancel_handle),
+/// keep a clone, and call [`CancelHandle::cancel`] to stop the command.
+#[derive(Clone, Debug, Default)]
+pub struct CancelHandle(Arc<AtomicBool>);
+
+impl CancelHandle {
+    /// Create a handle that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Kill any command using this handle, and any started with it in the future.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether [`CancelHandle::cancel`] has been called.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+impl PartialEq for CancelHandle {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for CancelHandle {}
+
+/// The reason that a process was killed by a [`Runner`] before it exited.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interruption {
+    /// The timeout of the [`Invocation`] elapsed.
+    TimedOut,
+    /// The [`CancelHandle`] of the [`Invocation`] was cancelled.
+    Cancelled,
+}
+
+/// Everything needed to start a process, as handed to [`Runner::run`].
+#[derive(Clone, Debug, PartialEq, Eq, Getters)]
+#[getset(get = "pub")]
+pub struct Invocation {
+    /// The path to the executable, usually `asdf`.
+    program: PathBuf,
+    /// The arguments passed to [`Invocation::program`], not including the program itself.
+    args: Vec<OsString>,
+    /// Environment variables to set in addition to those inherited from the current process.
+    envs: Vec<(OsString, OsString)>,
+    /// The working directory for the process, or [`None`] to inherit it.
+    current_dir: Option<PathBuf>,
+    /// How long the process may run before it is killed, or [`None`] to wait indefinitely.
+    timeout: Option<Duration>,
+    /// A handle that kills the process when cancelled.
+    cancel_handle: Option<CancelHandle>,
+}
+
+impl Invocation {
+    /// Create an invocation of `program` with no arguments.
+    pub fn new<P: Into<PathBuf>>(program: P) -> Self {
+        Self {
+            program: program.into(),
+            args: Vec::new(),
+            envs: Vec::new(),
+            current_dir: None,
+            timeout: None,
+            cancel_handle: None,
+        }
+    }
+
+    /// Append arguments.
+    pub fn with_args<S, I>(mut self, args: I) -> Self
+    where
+        S: AsRef<OsStr>,
+        I: IntoIterator<Item = S>,
+    {
+        self.args
+            .extend(args.into_iter().map(|x| x.as_ref().to_owned()));
+        self
+    }
+
+    /// Set an environment variable.
+    pub fn with_env<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: AsRef<OsStr>,
+        V: AsRef<OsStr>,
+    {
+        self.envs
+            .push((key.as_ref().to_owned(), value.as_ref().to_owned()));
+        self
+    }
+
+    /// Set the working directory.
+    pub fn with_current_dir<P: Into<PathBuf>>(mut self, current_dir: P) -> Self {
+        self.current_dir = Some(current_dir.into());
+        self
+    }
+
+    /// Set the timeout.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set the cancel handle.
+    pub fn with_cancel_handle(mut self, cancel_handle: CancelHandle) -> Self {
+        self.cancel_handle = Some(cancel_handle);
+        self
+    }
+
+    /// The program, arguments and environment variables of this invocation as a single line,
+    /// with each part escaped for a POSIX shell. This is only meant to be read by a human,
+    /// non-unicode characters are replaced.
+    ///
+    /// ```
+    /// use asdf::runner::Invocation;
+    ///
+    /// let invocation = Invocation::new("asdf")
+    ///     .with_env("ASDF_DATA_DIR", "/home/user/my data")
+    ///     .with_args(["plugin", "add", "nodejs"]);
+    ///
+    /// assert_eq!(
+    ///     invocation.command_line(),
+    ///     "ASDF_DATA_DIR='/home/user/my data' asdf plugin add nodejs"
+    /// );
+    /// ```
+    pub fn command_line(&self) -> String {
+        let envs = self.envs.iter().map(|(key, value)| {
+            format!(
+                "{}={}",
+                key.to_string_lossy(),
+                shell_escape(&value.to_string_lossy())
+            )
+        });
+        let program = std::iter::once(shell_escape(&self.program.to_string_lossy()).into_owned());
+        let args = self
+            .args
+            .iter()
+            .map(|x| shell_escape(&x.to_string_lossy()).into_owned());
+
+        envs.chain(program)
+            .chain(args)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Whether the process needs to be watched for a timeout or cancellation.
+    fn is_interruptible(&self) -> bool {
+        self.timeout.is_some() || self.cancel_handle.is_some()
+    }
+
+    /// The reason to kill the process, if it was started at `started`.
+    fn interruption(&self, started: Instant) -> Option<Interruption> {
+        if self
+            .cancel_handle
+            .as_ref()
+            .is_some_and(|x| x.is_cancelled())
+        {
+            Some(Interruption::Cancelled)
+        } else if self.timeout.is_some_and(|x| started.elapsed() >= x) {
+            Some(Interruption::TimedOut)
+        } else {
+            None
+        }
+    }
+
+    /// Wait until there is a reason to kill the process, if it was started at `started`.
+    #[cfg(feature = "tokio")]
+    async fn interrupted(&self, started: Instant) -> Interruption {
+        if !self.is_interruptible() {
+            return std::future::pending().await;
+        }
+        loop {
+            if let Some(interruption) = self.interruption(started) {
+                return interruption;
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+}
+
+/// Quote `text` for a POSIX shell, unless it only contains characters that never need quoting.
+pub(crate) fn shell_escape(text: &str) -> std::borrow::Cow<'_, str> {
+    let is_plain = |x: char| x.is_ascii_alphanumeric() || "%+,-./:=@_".contains(x);
+
+    if !text.is_empty() && text.chars().all(is_plain) {
+        text.into()
+    } else {
+        format!("'{}'", text.replace('\'', r"'\''")).into()
+    }
+}
+
+/// The result of a process that has exited, as returned by [`Runner::run`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Getters)]
+#[getset(get = "pub")]
+pub struct RunOutput {
+    /// The exit code, or [`None`] if the process was terminated by a signal.
+    code: Option<i32>,
+    /// Everything that the process wrote to standard output.
+    stdout: Vec<u8>,
+    /// Everything that the process wrote to standard error.
+    stderr: Vec<u8>,
+    /// Why the process was killed before it exited, in which case the output is incomplete.
+    interrupted: Option<Interruption>,
+}
+
+impl RunOutput {
+    /// Output of a process that exited with `code` and wrote nothing.
+    pub fn exited(code: i32) -> Self {
+        Self {
+            code: Some(code),
+            ..Self::default()
+        }
+    }
+
+    /// Output of a process that was terminated by a signal and wrote nothing.
+    pub fn signaled() -> Self {
+        Self::default()
+    }
+
+    /// Output of a process that was killed for `interruption` and wrote nothing.
+    pub fn killed(interruption: Interruption) -> Self {
+        Self {
+            interrupted: Some(interruption),
+            ..Self::default()
+        }
+    }
+
+    /// Replace the standard output.
+    pub fn with_stdout<B: Into<Vec<u8>>>(mut self, stdout: B) -> Self {
+        self.stdout = stdout.into();
+        self
+    }
+
+    /// Replace the standard error.
+    pub fn with_stderr<B: Into<Vec<u8>>>(mut self, stderr: B) -> Self {
+        self.stderr = stderr.into();
+        self
+    }
+
+    /// Whether the process exited with code `0`.
+    pub fn success(&self) -> bool {
+        self.code == Some(0)
+    }
+
+    /// Append a line read from `stream` to the matching buffer, and report it to `on_output`.
+    fn push_line(
+        &mut self,
+        stream: Stream,
+        line: &[u8],
+        on_output: &mut dyn FnMut(OutputEvent<'_>),
+    ) {
+        match stream {
+            Stream::Stdout => self.stdout.extend_from_slice(line),
+            Stream::Stderr => self.stderr.extend_from_slice(line),
+        }
+        stream.event(line, on_output);
+    }
+
+    /// Report every line of the captured output to `on_output`, standard output first.
+    fn replay(&self, on_output: &mut dyn FnMut(OutputEvent<'_>)) {
+        for line in self.stdout.split_inclusive(|x| *x == b'\n') {
+            Stream::Stdout.event(line, on_output);
+        }
+        for line in self.stderr.split_inclusive(|x| *x == b'\n') {
+            Stream::Stderr.event(line, on_output);
+        }
+    }
+}
+
+/// Something that can run an [`Invocation`] to completion and capture its output.
+pub trait Runner: Debug + Send + Sync {
+    /// Run the process described by `invocation`, and wait for it to exit.
+    ///
+    /// An [`Err`] is only returned when the process could not be run at all,
+    /// a non-zero exit code is reported through [`RunOutput::code`].
+    fn run(&self, invocation: &Invocation) -> std::io::Result<RunOutput>;
+
+    /// Like [`Runner::run`], but each line of output is reported to `on_output` as it is written.
+    ///
+    /// The default implementation calls [`Runner::run`] and reports every line after the process
+    /// has exited, standard output first, which is fine for runners that do not start processes.
+    fn run_streaming(
+        &self,
+        invocation: &Invocation,
+        on_output: &mut dyn FnMut(OutputEvent<'_>),
+    ) -> std::io::Result<RunOutput> {
+        let output = self.run(invocation)?;
+
+        output.replay(on_output);
+        Ok(output)
+    }
+
+    /// Run the process described by `invocation` without blocking the executor.
+    ///
+    /// The default implementation calls [`Runner::run`], which is fine for runners
+    /// that do not start processes, such as [`ReplayRunner`].
+    #[cfg(feature = "tokio")]
+    fn run_async<'a>(
+        &'a self,
+        invocation: &'a Invocation,
+    ) -> BoxFuture<'a, std::io::Result<RunOutput>> {
+        Box::pin(async move { self.run(invocation) })
+    }
+
+    /// Like [`Runner::run_streaming`], without blocking the executor.
+    ///
+    /// The default implementation calls [`Runner::run_async`] and reports every line after the process
+    /// has exited, standard output first.
+    #[cfg(feature = "tokio")]
+    fn run_streaming_async<'a>(
+        &'a self,
+        invocation: &'a Invocation,
+        on_output: &'a (dyn Fn(OutputEvent<'_>) + Send + Sync),
+    ) -> BoxFuture<'a, std::io::Result<RunOutput>> {
+        Box::pin(async move {
+            let output = self.run_async(invocation).await?;
+
+            output.replay(&mut |event| on_output(event));
+            Ok(output)
+        })
+    }
+}
+
+/// The default [`Runner`], which starts a real process with [`std::process::Command`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemRunner;
+
+impl SystemRunner {
+    /// Replace the current process with the one described by `invocation`, keeping its process ID,
+    /// standard input and output, and signal handling. This only returns if the process could not be started.
+    ///
+    /// The timeout and cancel handle of `invocation` are ignored, because nothing is left to enforce them.
+    #[cfg(unix)]
+    pub fn exec(invocation: &Invocation) -> std::io::Error {
+        use std::os::unix::process::CommandExt;
+
+        Self::process(invocation).exec()
+    }
+
+    fn process(invocation: &Invocation) -> Command {
+        let mut command = Command::new(&invocation.program);
+
+        command
+            .args(&invocation.args)
+            .envs(invocation.envs.iter().map(|(k, v)| (k, v)));
+
+        if let Some(current_dir) = &invocation.current_dir {
+            command.current_dir(current_dir);
+        }
+
+        command
+    }
+
+    fn command(invocation: &Invocation) -> Command {
+        let mut command = Self::process(invocation);
+
+        // Put the process in its own group, so that anything it starts can be killed with it.
+        #[cfg(unix)]
+        if invocation.is_interruptible() {
+            use std::os::unix::process::CommandExt;
+
+            command.process_group(0);
+        }
+
+        command
+    }
+
+    /// The command for `invocation` without blocking. As with [`SystemRunner::command`], only an interruptible
+    /// process gets its own group, which is then killed with everything it started if the future running it
+    /// is dropped, see [`KillOnDrop`]. Any other process stays in the caller's group, so that Ctrl-C in a
+    /// terminal reaches it and its plugin scripts, and dropping the future only kills `asdf` itself.
+    #[cfg(feature = "tokio")]
+    fn command_async(invocation: &Invocation) -> tokio::process::Command {
+        let mut command = Self::process(invocation);
+
+        #[cfg(unix)]
+        if invocation.is_interruptible() {
+            use std::os::unix::process::CommandExt;
+
+            command.process_group(0);
+        }
+
+        let mut command = tokio::process::Command::from(command);
+        command.kill_on_drop(true);
+        command
+    }
+}
+
+/// Kills the process group of a child when dropped, unless [`KillOnDrop::disarm`] was called after waiting for it.
+/// This is what stops `asdf` and its plugin scripts when a caller drops the future running an interruptible
+/// invocation, such as with `tokio::time::timeout` or `tokio::select!`, where `kill_on_drop` would only kill
+/// `asdf` itself. Only a child that leads its own group, see [`SystemRunner::command_async`], may be guarded.
+#[cfg(feature = "tokio")]
+struct KillOnDrop(Option<u32>);
+
+#[cfg(feature = "tokio")]
+impl KillOnDrop {
+    fn new(child: &tokio::process::Child) -> Self {
+        Self(child.id())
+    }
+
+    /// The child has been waited for, so its ID may already belong to another process.
+    fn disarm(&mut self) {
+        self.0 = None;
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Drop for KillOnDrop {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Some(pid) = self.0 {
+            kill_group(pid);
+        }
+    }
+}
+
+impl Runner for SystemRunner {
+    fn run(&self, invocation: &Invocation) -> std::io::Result<RunOutput> {
+        if invocation.is_interruptible() {
+            return self.run_streaming(invocation, &mut |_| {});
+        }
+
+        let output = Self::command(invocation).output()?;
+
+        Ok(RunOutput {
+            code: output.status.code(),
+            stdout: output.stdout,
+            stderr: output.stderr,
+            interrupted: None,
+        })
+    }
+
+    fn run_streaming(
+        &self,
+        invocation: &Invocation,
+        on_output: &mut dyn FnMut(OutputEvent<'_>),
+    ) -> std::io::Result<RunOutput> {
+        let started = Instant::now();
+        let mut child = Self::command(invocation)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        // Both pipes are read on their own threads so that neither can fill up and block the process,
+        // the lines are sent back here so that `on_output` is only called from the current thread.
+        let (sender, receiver) = mpsc::channel();
+        let readers = [
+            read_lines(Stream::Stdout, child.stdout.take().unwrap(), sender.clone()),
+            read_lines(Stream::Stderr, child.stderr.take().unwrap(), sender),
+        ];
+        let mut output = RunOutput::default();
+
+        loop {
+            if output.interrupted.is_none() {
+                output.interrupted = invocation.interruption(started);
+
+                if output.interrupted.is_some() {
+                    kill_tree(&mut child)?;
+                }
+            }
+            match receiver.recv_timeout(POLL_INTERVAL) {
+                Ok((stream, line)) => output.push_line(stream, &line, on_output),
+                Err(mpsc::RecvTimeoutError::Timeout) => (),
+                // Both pipes are closed, but that does not mean the process has exited.
+                Err(mpsc::RecvTimeoutError::Disconnected) => match child.try_wait()? {
+                    Some(status) => {
+                        output.code = status.code();
+                        break;
+                    }
+                    None => thread::sleep(POLL_INTERVAL),
+                },
+            }
+        }
+        for reader in readers {
+            reader.join().expect("output reader panicked")?;
+        }
+
+        Ok(output)
+    }
+
+    #[cfg(feature = "tokio")]
+    fn run_async<'a>(
+        &'a self,
+        invocation: &'a Invocation,
+    ) -> BoxFuture<'a, std::io::Result<RunOutput>> {
+        if invocation.is_interruptible() {
+            return self.run_streaming_async(invocation, &|_| {});
+        }
+
+        Box::pin(async move {
+            let child = Self::command_async(invocation)
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()?;
+            let output = child.wait_with_output().await?;
+
+            Ok(RunOutput {
+                code: output.status.code(),
+                stdout: output.stdout,
+                stderr: output.stderr,
+                interrupted: None,
+            })
+        })
+    }
+
+    #[cfg(feature = "tokio")]
+    fn run_streaming_async<'a>(
+        &'a self,
+        invocation: &'a Invocation,
+        on_output: &'a (dyn Fn(OutputEvent<'_>) + Send + Sync),
+    ) -> BoxFuture<'a, std::io::Result<RunOutput>> {
+        Box::pin(async move {
+            let started = Instant::now();
+            let mut child = Self::command_async(invocation)
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()?;
+            let mut guard = KillOnDrop::new(&child);
+            let mut interrupted = None;
+
+            let stdout = child.stdout.take().unwrap();
+            let stderr = child.stderr.take().unwrap();
+            let pipes = async {
+                tokio::try_join!(
+                    read_lines_async(Stream::Stdout, stdout, on_output),
+                    read_lines_async(Stream::Stderr, stderr, on_output),
+                )
+            };
+            tokio::pin!(pipes);
+
+            let mut read = None;
+            tokio::select! {
+                result = &mut pipes => read = Some(result),
+                interruption = invocation.interrupted(started) => interrupted = Some(interruption),
+            }
+            if interrupted.is_some() {
+                kill_tree_async(&mut child)?;
+            }
+            let (stdout, stderr) = match read {
+                Some(result) => result?,
+                // The pipes are closed once the process has been killed, so this keeps the partial output.
+                None => pipes.await?,
+            };
+
+            let mut status = None;
+            tokio::select! {
+                result = child.wait() => status = Some(result?),
+                interruption = invocation.interrupted(started), if interrupted.is_none() => {
+                    interrupted = Some(interruption)
+                }
+            }
+            let status = match status {
+                Some(status) => status,
+                None => {
+                    kill_tree_async(&mut child)?;
+                    child.wait().await?
+                }
+            };
+            guard.disarm();
+
+            Ok(RunOutput {
+                code: status.code(),
+                stdout,
+                stderr,
+                interrupted,
+            })
+        })
+    }
+}
+
+/// Kill `child` and, on Unix, every process in its group.
+fn kill_tree(child: &mut Child) -> std::io::Result<()> {
+    #[cfg(unix)]
+    if kill_group(child.id()) {
+        return Ok(());
+    }
+    child.kill()
+}
+
+/// Kill `child` and, on Unix, every process in its group.
+#[cfg(feature = "tokio")]
+fn kill_tree_async(child: &mut tokio::process::Child) -> std::io::Result<()> {
+    #[cfg(unix)]
+    if child.id().is_some_and(kill_group) {
+        return Ok(());
+    }
+    child.start_kill()
+}
+
+/// Kill the process group led by `pid`, returning whether the signal was sent.
+#[cfg(unix)]
+fn kill_group(pid: u32) -> bool {
+    // SAFETY: `kill` has no memory safety requirements, a negative PID addresses the process group.
+    unsafe { libc::kill(-(pid as libc::pid_t), libc::SIGKILL) == 0 }
+}
+
+/// Read `reader` on a new thread, sending each line (with its terminator) through `sender`.
+fn read_lines<R>(
+    stream: Stream,
+    reader: R,
+    sender: mpsc::Sender<(Stream, Vec<u8>)>,
+) -> thread::JoinHandle<std::io::Result<()>>
+where
+    R: Read + Send + 'static,
+{
+    thread::spawn(move || {
+        let mut reader = BufReader::new(reader);
+
+        loop {
+            let mut line = Vec::new();
+
+            if reader.read_until(b'\n', &mut line)? == 0 {
+                return Ok(());
+            }
+            // The receiver only hangs up after the process has been dealt with.
+            let _ = sender.send((stream, line));
+        }
+    })
+}
+
+/// Read `reader` to the end, reporting each line to `on_output` and returning everything that was read.
+#[cfg(feature = "tokio")]
+async fn read_lines_async<R>(
+    stream: Stream,
+    reader: R,
+    on_output: &(dyn Fn(OutputEvent<'_>) + Send + Sync),
+) -> std::io::Result<Vec<u8>>
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    use tokio::io::AsyncBufReadExt;
+
+    let mut reader = tokio::io::BufReader::new(reader);
+    let mut buffer = Vec::new();
+
+    loop {
+        let start = buffer.len();
+
+        if reader.read_until(b'\n', &mut buffer).await? == 0 {
+            return Ok(buffer);
+        }
+        stream.event(&buffer[start..], &mut |event| on_output(event));
+    }
+}
+
+/// A [`Runner`] that replies to each invocation with the next canned [`RunOutput`]
+/// from a script, without starting any processes.
+///
+/// Each expected invocation is matched by its arguments (not including the program),
+/// in the order they were added with [`ReplayRunner::expect`]. An invocation that does not
+/// match, or that is made after the script is exhausted, fails with an [`std::io::Error`].
+///
+/// Clones share the same script, so a clone can be kept to inspect [`ReplayRunner::invocations`]
+/// after another has been given to a client.
+///
+/// ```
+/// use asdf::{runner::{ReplayRunner, RunOutput}, Asdf, AsdfVersion};
+///
+/// let runner = ReplayRunner::new().expect(
+///     ["plugin", "list", "--urls", "--refs"],
+///     RunOutput::exited(0).with_stdout("nodejs https://github.com/asdf-vm/asdf-nodejs.git master 5f1e9b4\n"),
+/// );
+/// let asdf = Asdf::new()
+///     .with_version(AsdfVersion::new(0, 14, 1))
+///     .with_runner(runner.clone());
+///
+/// let plugins = asdf.plugin_list().unwrap();
+///
+/// assert_eq!(plugins[0].name(), "nodejs");
+/// assert!(runner.is_finished());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ReplayRunner {
+    state: Arc<Mutex<ReplayState>>,
+}
+
+#[derive(Debug, Default)]
+struct ReplayState {
+    script: VecDeque<(Vec<OsString>, RunOutput)>,
+    invocations: Vec<Invocation>,
+}
+
+impl ReplayRunner {
+    /// Create a runner with an empty script.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an invocation to the end of the script, which will be answered with `reply`.
+    pub fn expect<S, I>(self, args: I, reply: RunOutput) -> Self
+    where
+        S: AsRef<OsStr>,
+        I: IntoIterator<Item = S>,
+    {
+        let args = args.into_iter().map(|x| x.as_ref().to_owned()).collect();
+
+        self.state.lock().unwrap().script.push_back((args, reply));
+        self
+    }
+
+    /// Every invocation that has been run so far, including those that did not match.
+    pub fn invocations(&self) -> Vec<Invocation> {
+        self.state.lock().unwrap().invocations.clone()
+    }
+
+    /// Whether every expected invocation in the script has been run.
+    pub fn is_finished(&self) -> bool {
+        self.state.lock().unwrap().script.is_empty()
+    }
+}
+
+impl Runner for ReplayRunner {
+    fn run(&self, invocation: &Invocation) -> std::io::Result<RunOutput> {
+        let mut state = self.state.lock().unwrap();
+
+        state.invocations.push(invocation.clone());
+
+        match state.script.front() {
+            Some((args, _)) if args == &invocation.args => Ok(state.script.pop_front().unwrap().1),
+            Some((args, _)) => Err(std::io::Error::other(format!(
+                "unexpected invocation with arguments {:?}, expected {:?}",
+                invocation.args, args
+            ))),
+            None => Err(std::io::Error::other(format!(
+                "unexpected invocation with arguments {:?}, the script is finished",
+                invocation.args
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replay_runs_script_in_order() {
+        let runner = ReplayRunner::new()
+            .expect(["one"], RunOutput::exited(0).with_stdout("first"))
+            .expect(["two"], RunOutput::exited(1).with_stderr("second"));
+
+        let first = runner
+            .run(&Invocation::new("asdf").with_args(["one"]))
+            .unwrap();
+        let second = runner
+            .run(&Invocation::new("asdf").with_args(["two"]))
+            .unwrap();
+
+        assert!(first.success());
+        assert_eq!(first.stdout(), b"first");
+        assert_eq!(second.code(), &Some(1));
+        assert_eq!(second.stderr(), b"second");
+        assert!(runner.is_finished());
+        assert_eq!(runner.invocations().len(), 2);
+    }
+
+    #[test]
+    fn replay_streams_captured_lines() {
+        let runner = ReplayRunner::new().expect(
+            ["install"],
+            RunOutput::exited(0)
+                .with_stdout("Downloading...\r\nInstalled\n")
+                .with_stderr("warning"),
+        );
+        let mut events = Vec::new();
+
+        let output = runner
+            .run_streaming(
+                &Invocation::new("asdf").with_args(["install"]),
+                &mut |event| events.push(format!("{event:?}")),
+            )
+            .unwrap();
+
+        assert_eq!(output.stdout(), b"Downloading...\r\nInstalled\n");
+        assert_eq!(
+            events,
+            [
+                r#"Stdout("Downloading...")"#,
+                r#"Stdout("Installed")"#,
+                r#"Stderr("warning")"#,
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn system_streams_both_pipes() {
+        let mut events = Vec::new();
+
+        let output = SystemRunner
+            .run_streaming(
+                &Invocation::new("sh").with_args(["-c", "echo out; echo err >&2; exit 3"]),
+                &mut |event| events.push(format!("{event:?}")),
+            )
+            .unwrap();
+
+        assert_eq!(output.code(), &Some(3));
+        assert_eq!(output.stdout(), b"out\n");
+        assert_eq!(output.stderr(), b"err\n");
+        events.sort();
+        assert_eq!(events, [r#"Stderr("err")"#, r#"Stdout("out")"#]);
+    }
+
+    #[cfg(all(unix, feature = "tokio"))]
+    #[tokio::test]
+    async fn system_streams_both_pipes_async() {
+        let events = Mutex::new(Vec::new());
+        let on_output = |event: OutputEvent<'_>| events.lock().unwrap().push(format!("{event:?}"));
+
+        let output = SystemRunner
+            .run_streaming_async(
+                &Invocation::new("sh").with_args(["-c", "echo out; echo err >&2"]),
+                &on_output,
+            )
+            .await
+            .unwrap();
+
+        assert!(output.success());
+        assert_eq!(output.stderr(), b"err\n");
+        let mut events = events.into_inner().unwrap();
+        events.sort();
+        assert_eq!(events, [r#"Stderr("err")"#, r#"Stdout("out")"#]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn system_kills_process_group_on_timeout() {
+        let started = Instant::now();
+
+        let output = SystemRunner
+            .run(
+                &Invocation::new("sh")
+                    .with_args(["-c", "echo partial; sleep 30 & wait"])
+                    .with_timeout(Duration::from_millis(200)),
+            )
+            .unwrap();
+
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert_eq!(output.interrupted(), &Some(Interruption::TimedOut));
+        assert_eq!(output.code(), &None);
+        assert_eq!(output.stdout(), b"partial\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn system_kills_process_when_cancelled() {
+        let cancel = CancelHandle::new();
+        let canceller = cancel.clone();
+
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            canceller.cancel();
+        });
+
+        let output = SystemRunner
+            .run(
+                &Invocation::new("sh")
+                    .with_args(["-c", "sleep 30"])
+                    .with_cancel_handle(cancel),
+            )
+            .unwrap();
+
+        assert_eq!(output.interrupted(), &Some(Interruption::Cancelled));
+    }
+
+    #[cfg(all(unix, feature = "tokio"))]
+    #[tokio::test]
+    async fn system_kills_process_group_on_timeout_async() {
+        let output = SystemRunner
+            .run_async(
+                &Invocation::new("sh")
+                    .with_args(["-c", "echo partial; sleep 30 & wait"])
+                    .with_timeout(Duration::from_millis(200)),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(output.interrupted(), &Some(Interruption::TimedOut));
+        assert_eq!(output.stdout(), b"partial\n");
+    }
+
+    #[cfg(all(target_os = "linux", feature = "tokio"))]
+    #[tokio::test]
+    async fn system_kills_process_group_when_dropped_async() {
+        let pid_file = std::env::temp_dir().join(format!("asdf-drop-{}", std::process::id()));
+        let invocation = Invocation::new("sh")
+            .with_args([
+                "-c".to_owned(),
+                format!("sleep 30 & echo $! > '{}'; wait", pid_file.display()),
+            ])
+            .with_cancel_handle(CancelHandle::new());
+
+        let dropped = tokio::time::timeout(
+            Duration::from_millis(300),
+            SystemRunner.run_async(&invocation),
+        )
+        .await;
+        assert!(dropped.is_err());
+
+        let pid = std::fs::read_to_string(&pid_file).unwrap();
+        std::fs::remove_file(&pid_file).unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        // The sleep is gone, or a zombie waiting to be reaped by whichever process adopted it.
+        let stat =
+            std::fs::read_to_string(format!("/proc/{}/stat", pid.trim())).unwrap_or_default();
+        assert!(stat.is_empty() || stat.contains(") Z "), "{stat}");
+    }
+
+    #[test]
+    fn replay_rejects_unexpected_invocation() {
+        let runner = ReplayRunner::new().expect(["one"], RunOutput::exited(0));
+
+        assert!(runner
+            .run(&Invocation::new("asdf").with_args(["two"]))
+            .is_err());
+        assert!(!runner.is_finished());
+
+        runner
+            .run(&Invocation::new("asdf").with_args(["one"]))
+            .unwrap();
+
+        assert!(runner
+            .run(&Invocation::new("asdf").with_args(["one"]))
+            .is_err());
+    }
+}
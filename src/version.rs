@@ -0,0 +1,103 @@
+use std::{fmt::Display, str::FromStr};
+
+use getset::CopyGetters;
+
+/// A release of `asdf`, used to adapt commands and output parsing to the version that is installed.
+/// See [`Asdf::version`](crate::Asdf::version).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct AsdfVersion {
+    major: u32,
+    minor: u32,
+    patch: u32,
+}
+
+impl AsdfVersion {
+    /// The first release of the rewrite in Go, which changed the command set and many messages.
+    pub const GO_REWRITE: Self = Self::new(0, 16, 0);
+
+    /// The release `major.minor.patch`, such as `AsdfVersion::new(0, 14, 1)` for `v0.14.1`.
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+}
+
+impl Display for AsdfVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl FromStr for AsdfVersion {
+    type Err = &'static str;
+
+    /// Parse the output of `asdf --version`, which is formatted like `v0.14.1-f00f759` before the rewrite in Go,
+    /// and like `asdf version 0.16.7` (possibly with a `v` prefix or a revision suffix) after it.
+    /// The first word that starts with a version number is used, and a missing patch number is assumed to be `0`.
+    fn from_str(other: &str) -> std::result::Result<Self, Self::Err> {
+        let word = other
+            .split_whitespace()
+            .map(|x| x.strip_prefix('v').unwrap_or(x))
+            .find(|x| x.starts_with(|c: char| c.is_ascii_digit()))
+            .ok_or("no version number was found")?;
+        let end = word
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(word.len());
+        let mut parts = word[..end].split('.').map(|x| x.parse::<u32>());
+
+        let major = match parts.next() {
+            Some(Ok(major)) => major,
+            _ => return Err("the major version is not a number"),
+        };
+        let minor = match parts.next() {
+            Some(Ok(minor)) => minor,
+            _ => return Err("the minor version is not a number"),
+        };
+        let patch = match parts.next() {
+            Some(Ok(patch)) => patch,
+            Some(Err(_)) => return Err("the patch version is not a number"),
+            None => 0,
+        };
+
+        Ok(Self::new(major, minor, patch))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_bash_version() {
+        assert_eq!("v0.14.1-f00f759\n".parse(), Ok(AsdfVersion::new(0, 14, 1)));
+        assert_eq!("v0.8.1\n".parse(), Ok(AsdfVersion::new(0, 8, 1)));
+    }
+
+    #[test]
+    fn parses_go_version() {
+        assert_eq!(
+            "asdf version 0.16.7\n".parse(),
+            Ok(AsdfVersion::new(0, 16, 7))
+        );
+        assert_eq!(
+            "asdf version v0.18.0 (revision 2ab1c8e)\n".parse(),
+            Ok(AsdfVersion::new(0, 18, 0))
+        );
+    }
+
+    #[test]
+    fn rejects_missing_version() {
+        assert!("asdf version unknown".parse::<AsdfVersion>().is_err());
+        assert!("v1".parse::<AsdfVersion>().is_err());
+    }
+
+    #[test]
+    fn orders_releases() {
+        assert!(AsdfVersion::new(0, 15, 9) < AsdfVersion::GO_REWRITE);
+        assert!(AsdfVersion::new(1, 0, 0) > AsdfVersion::GO_REWRITE);
+    }
+}